@@ -0,0 +1,373 @@
+//! `hmt`, a command-line tool for launching and managing escrows and key-value store entries.
+
+use std::{convert::TryInto, error::Error, fs, path::{Path, PathBuf}, str::FromStr};
+
+use node_template_client::{calls, Client, ExtrinsicEvents, Signer, WaitFor};
+use node_template_runtime::{
+	pallet_escrow::{EscrowId, FactoryId, FixedFeeTiming, JobTerms, ManifestHashAlgorithm, Oracle, OracleRole, RawEvent},
+	AccountId, Balance, Call,
+};
+use sp_runtime::Percent;
+use structopt::StructOpt;
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+/// The algorithm of the manifest hashes computed by `read_manifest`.
+const CANONICAL: ManifestHashAlgorithm = ManifestHashAlgorithm::Canonical;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "hmt", about = "Launch and manage escrows and key-value store entries.")]
+struct Cli {
+	/// HTTP RPC endpoint of the node.
+	#[structopt(long, default_value = "http://localhost:9933")]
+	url: String,
+
+	/// Secret URI of the signing key, e.g. `//Alice` on a development chain.
+	#[structopt(long, conflicts_with = "keystore-file")]
+	seed: Option<String>,
+
+	/// Keystore file holding the secret phrase or seed of the signing key.
+	#[structopt(long, parse(from_os_str))]
+	keystore_file: Option<PathBuf>,
+
+	/// Wait until extrinsics are finalized instead of included in a block.
+	#[structopt(long)]
+	finalized: bool,
+
+	#[structopt(subcommand)]
+	command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+	/// Manage escrow factories.
+	Factory(FactoryCommand),
+	/// Manage escrows.
+	Escrow(EscrowCommand),
+	/// Read and write the key-value store.
+	Kv(KvCommand),
+}
+
+#[derive(Debug, StructOpt)]
+enum FactoryCommand {
+	/// Create a factory and print its id.
+	Create,
+}
+
+#[derive(Debug, StructOpt)]
+enum EscrowCommand {
+	/// Create an escrow from a manifest and print its id.
+	Create {
+		/// The factory of the escrow.
+		#[structopt(long)]
+		factory: FactoryId,
+		/// The manifest file. It is validated and its canonical hash is stored with the escrow.
+		#[structopt(long, parse(from_os_str))]
+		manifest: PathBuf,
+		/// The URL the manifest is published at.
+		#[structopt(long)]
+		manifest_url: String,
+		/// An oracle as `ACCOUNT:ROLE:FEE_PERCENT[:FIXED_FEE[:upfront|completion]]`,
+		/// ROLE being `reputation`, `recording`, `exchange` or `other`.
+		#[structopt(long = "oracle")]
+		oracles: Vec<OracleArg>,
+		/// Store the price, task count and repeats of the manifest as job terms, bounding
+		/// the payouts. The price is converted with the given number of token decimals.
+		#[structopt(long)]
+		token_decimals: Option<u32>,
+	},
+	/// Transfer funds to an escrow.
+	Fund {
+		id: EscrowId,
+		amount: Balance,
+	},
+	/// Print the state and balance of an escrow.
+	Status {
+		id: EscrowId,
+	},
+	/// Pay out workers as listed in a CSV file of `account,amount` lines.
+	///
+	/// More recipients than the bulk accounts limit are refused unless `--chunked` is given.
+	Payout {
+		id: EscrowId,
+		#[structopt(long, parse(from_os_str))]
+		csv: PathBuf,
+		/// Pay out in one extrinsic per chunk of at most the bulk accounts limit of recipients.
+		#[structopt(long)]
+		chunked: bool,
+		/// Skip the given number of chunks, paid out by an earlier run that failed.
+		#[structopt(long, default_value = "0", requires = "chunked")]
+		skip: usize,
+	},
+	/// Cancel an escrow, refunding its balance to the creator.
+	Cancel {
+		id: EscrowId,
+	},
+}
+
+#[derive(Debug, StructOpt)]
+enum KvCommand {
+	/// Store a value of the signing account.
+	Set {
+		key: String,
+		value: String,
+	},
+	/// Print a value, of the signing account unless `--account` is given.
+	Get {
+		key: String,
+		#[structopt(long)]
+		account: Option<AccountId>,
+	},
+}
+
+#[derive(Debug)]
+struct OracleArg(Oracle<AccountId, Balance>);
+
+impl FromStr for OracleArg {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let parts: Vec<&str> = s.split(':').collect();
+		if parts.len() < 3 || parts.len() > 5 {
+			return Err(format!("Expected `ACCOUNT:ROLE:FEE_PERCENT[:FIXED_FEE[:TIMING]]`, got `{}`", s));
+		}
+		let account = AccountId::from_str(parts[0]).map_err(|e| format!("Invalid account `{}`: {}", parts[0], e))?;
+		let role = match parts[1] {
+			"reputation" => OracleRole::Reputation,
+			"recording" => OracleRole::Recording,
+			"exchange" => OracleRole::Exchange,
+			"other" => OracleRole::Other,
+			role => return Err(format!("Unknown oracle role `{}`", role)),
+		};
+		let fee = parts[2].parse::<u8>().ok().filter(|fee| *fee <= 100)
+			.ok_or_else(|| format!("Invalid fee percentage `{}`", parts[2]))?;
+		let fixed_fee = match parts.get(3) {
+			Some(fixed_fee) => fixed_fee.parse().map_err(|_| format!("Invalid fixed fee `{}`", fixed_fee))?,
+			None => 0,
+		};
+		let fixed_fee_timing = match parts.get(4).copied() {
+			None | Some("completion") => FixedFeeTiming::Completion,
+			Some("upfront") => FixedFeeTiming::Upfront,
+			Some(timing) => return Err(format!("Unknown fixed fee timing `{}`", timing)),
+		};
+		Ok(OracleArg(Oracle { account, role, fee: Percent::from_percent(fee), fixed_fee, fixed_fee_timing }))
+	}
+}
+
+fn main() {
+	let cli = Cli::from_args();
+	if let Err(e) = run(cli) {
+		eprintln!("Error: {}", e);
+		std::process::exit(1);
+	}
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+	let client = Client::connect(cli.url.clone())?;
+	let wait = if cli.finalized { WaitFor::Finalized } else { WaitFor::InBlock };
+
+	match cli.command {
+		Command::Factory(FactoryCommand::Create) => {
+			let events = submit(&client, &signer(&cli.seed, &cli.keystore_file)?, calls::escrow::create_factory(), wait)?;
+			let id = events.escrow().find_map(|event| match event {
+				RawEvent::FactoryCreated(id, _) => Some(*id),
+				_ => None,
+			}).ok_or("Missing FactoryCreated event")?;
+			println!("{}", id);
+		}
+		Command::Escrow(EscrowCommand::Create { factory, manifest, manifest_url, oracles, token_decimals }) => {
+			let (hash, terms) = read_manifest(&manifest, token_decimals)?;
+			let oracles = oracles.into_iter().map(|oracle| oracle.0).collect();
+			let url = manifest_url.into_bytes();
+			let call = match terms {
+				Some(terms) => calls::escrow::create_from_terms(url, hash, CANONICAL, factory, oracles, terms),
+				None => calls::escrow::create(url, hash, CANONICAL, factory, oracles),
+			};
+			let events = submit(&client, &signer(&cli.seed, &cli.keystore_file)?, call, wait)?;
+			println!("{}", events.created_escrow().ok_or("Missing Pending event")?);
+		}
+		Command::Escrow(EscrowCommand::Fund { id, amount }) => {
+			submit(&client, &signer(&cli.seed, &cli.keystore_file)?, calls::escrow::fund(id, amount), wait)?;
+		}
+		Command::Escrow(EscrowCommand::Status { id }) => {
+			let escrow = client.escrow(id)?.ok_or_else(|| format!("Escrow {} does not exist", id))?;
+			println!("{:#?}", escrow);
+			println!("balance: {}", client.free_balance(escrow.account())?);
+			let manifest = match client.manifest_check(id)? {
+				Some(true) => "matches its hash",
+				Some(false) => "does not match its hash",
+				None => "not checked",
+			};
+			println!("manifest: {}", manifest);
+		}
+		Command::Escrow(EscrowCommand::Payout { id, csv, chunked, skip }) => {
+			let signer = signer(&cli.seed, &cli.keystore_file)?;
+			let payouts = parse_payouts(&fs::read_to_string(&csv)?)?;
+			let limit = match client.bulk_accounts_limit()? {
+				0 => return Err("The bulk accounts limit of the chain is not set".into()),
+				limit => limit as usize,
+			};
+			let chunks = payout_chunks(&payouts, limit, chunked, skip)?;
+			let total = chunks.len() + skip;
+			for (number, chunk) in (skip + 1..).zip(chunks) {
+				let (recipients, amounts) = chunk.iter().cloned().unzip();
+				submit(&client, &signer, calls::escrow::bulk_payout(id, recipients, amounts), wait).map_err(|e| {
+					format!("Chunk {} of {} failed: {}. Resume with `--chunked --skip {}`", number, total, e, number - 1)
+				})?;
+				eprintln!("Paid out chunk {} of {}", number, total);
+			}
+		}
+		Command::Escrow(EscrowCommand::Cancel { id }) => {
+			submit(&client, &signer(&cli.seed, &cli.keystore_file)?, calls::escrow::cancel(id), wait)?;
+		}
+		Command::Kv(KvCommand::Set { key, value }) => {
+			let call = calls::kvstore::set(key.into_bytes(), value.into_bytes());
+			submit(&client, &signer(&cli.seed, &cli.keystore_file)?, call, wait)?;
+		}
+		Command::Kv(KvCommand::Get { key, account }) => {
+			let account = match account {
+				Some(account) => account,
+				None => signer(&cli.seed, &cli.keystore_file)?.account().clone(),
+			};
+			let value = client.kvstore_get(&account, key.as_bytes(), None)?.ok_or("No value")?;
+			match String::from_utf8(value) {
+				Ok(text) => println!("{}", text),
+				Err(e) => println!("0x{}", hex::encode(e.as_bytes())),
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The signer given by `--seed` or `--keystore-file`.
+fn signer(seed: &Option<String>, keystore_file: &Option<PathBuf>) -> CliResult<Signer> {
+	let uri = match (seed, keystore_file) {
+		(Some(seed), _) => seed.clone(),
+		// Keystore files written by the node hold the phrase or seed as a JSON string.
+		(None, Some(path)) => {
+			let content = fs::read_to_string(path)?;
+			serde_json::from_str::<String>(&content).unwrap_or_else(|_| content.trim().to_string())
+		}
+		(None, None) => return Err("Either `--seed` or `--keystore-file` is required".into()),
+	};
+	Ok(Signer::from_uri(&uri)?)
+}
+
+fn submit(client: &Client, signer: &Signer, call: Call, wait: WaitFor) -> CliResult<ExtrinsicEvents> {
+	let events = client.submit_and_wait(signer, call, wait)?;
+	eprintln!("Included in block {:?}", events.block);
+	for event in events.escrow() {
+		eprintln!("{:?}", event);
+	}
+	for event in events.kvstore() {
+		eprintln!("{:?}", event);
+	}
+	Ok(events)
+}
+
+/// Validate the manifest at `path` and compute the hash stored with the escrow, and the job terms
+/// if `token_decimals` are given.
+fn read_manifest(path: &Path, token_decimals: Option<u32>) -> CliResult<(Vec<u8>, Option<JobTerms<Balance>>)> {
+	let json = fs::read(path)?;
+	let manifest = manifest::Manifest::from_slice(&json)?;
+	let terms = match token_decimals {
+		Some(decimals) => Some(JobTerms {
+			total_tasks: manifest.job_total_tasks.try_into().map_err(|_| "job_total_tasks is too large")?,
+			price_per_task: manifest.task_bid_price_units(decimals)
+				.ok_or("task_bid_price is more precise than the token or too large")?,
+			min_repeats: manifest.requester_min_repeats,
+			max_repeats: manifest.requester_max_repeats,
+		}),
+		None => None,
+	};
+	Ok((manifest::hash(&json)?.to_vec(), terms))
+}
+
+/// Parse `account,amount` lines, skipping empty lines and an `account,amount` header.
+fn parse_payouts(csv: &str) -> CliResult<Vec<(AccountId, Balance)>> {
+	let mut payouts = Vec::new();
+	for (number, line) in csv.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || (number == 0 && line.eq_ignore_ascii_case("account,amount")) {
+			continue;
+		}
+		let mut fields = line.split(',').map(str::trim);
+		let (account, amount) = match (fields.next(), fields.next(), fields.next()) {
+			(Some(account), Some(amount), None) => (account, amount),
+			_ => return Err(format!("Line {}: expected `account,amount`", number + 1).into()),
+		};
+		let account = AccountId::from_str(account)
+			.map_err(|e| format!("Line {}: invalid account `{}`: {}", number + 1, account, e))?;
+		let amount = amount.parse()
+			.map_err(|_| format!("Line {}: invalid amount `{}`", number + 1, amount))?;
+		payouts.push((account, amount));
+	}
+	Ok(payouts)
+}
+
+/// Split `payouts` into bulk payouts of at most `limit` recipients, leaving out the first `skip`.
+///
+/// Splitting has to be asked for with `chunked`, as repeating a partially failed run would pay out
+/// the chunks that succeeded again.
+fn payout_chunks<T>(payouts: &[T], limit: usize, chunked: bool, skip: usize) -> CliResult<Vec<&[T]>> {
+	if payouts.len() > limit && !chunked {
+		return Err(format!(
+			"{} recipients exceed the bulk accounts limit of {}, pass `--chunked` to pay them out in chunks",
+			payouts.len(), limit,
+		).into());
+	}
+	let chunks: Vec<_> = payouts.chunks(limit).collect();
+	if skip > chunks.len() {
+		return Err(format!("Cannot skip {} of {} chunks", skip, chunks.len()).into());
+	}
+	Ok(chunks[skip..].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
+	#[test]
+	fn parses_oracles() {
+		let oracle = OracleArg::from_str(&format!("{}:reputation:10", ALICE)).unwrap().0;
+		assert_eq!(oracle.account, AccountId::from_str(ALICE).unwrap());
+		assert_eq!(oracle.role, OracleRole::Reputation);
+		assert_eq!(oracle.fee, Percent::from_percent(10));
+		assert_eq!((oracle.fixed_fee, oracle.fixed_fee_timing), (0, FixedFeeTiming::Completion));
+
+		let oracle = OracleArg::from_str(&format!("{}:exchange:5:100:upfront", BOB)).unwrap().0;
+		assert_eq!((oracle.fixed_fee, oracle.fixed_fee_timing), (100, FixedFeeTiming::Upfront));
+
+		assert!(OracleArg::from_str(&format!("{}:reputation", ALICE)).is_err());
+		assert!(OracleArg::from_str(&format!("{}:judge:10", ALICE)).is_err());
+		assert!(OracleArg::from_str(&format!("{}:reputation:101", ALICE)).is_err());
+	}
+
+	#[test]
+	fn parses_payouts() {
+		let csv = format!("account,amount\n{},100\n\n{}, 250\n", ALICE, BOB);
+		let payouts = parse_payouts(&csv).unwrap();
+		assert_eq!(payouts, vec![
+			(AccountId::from_str(ALICE).unwrap(), 100),
+			(AccountId::from_str(BOB).unwrap(), 250),
+		]);
+
+		assert!(parse_payouts(ALICE).is_err());
+		assert!(parse_payouts(&format!("{},1,2", ALICE)).is_err());
+		assert!(parse_payouts(&format!("{},-1", ALICE)).is_err());
+	}
+
+	#[test]
+	fn chunks_payouts() {
+		let payouts = [1, 2, 3, 4, 5];
+		assert_eq!(payout_chunks(&payouts, 5, false, 0).unwrap(), vec![&payouts[..]]);
+		assert!(payout_chunks(&payouts, 2, false, 0).is_err());
+		assert_eq!(payout_chunks(&payouts, 2, true, 0).unwrap(), vec![&[1, 2][..], &[3, 4], &[5]]);
+		assert_eq!(payout_chunks(&payouts, 2, true, 2).unwrap(), vec![&[5][..]]);
+		assert!(payout_chunks(&payouts, 2, true, 3).unwrap().is_empty());
+		assert!(payout_chunks(&payouts, 2, true, 4).is_err());
+	}
+}
@@ -0,0 +1,148 @@
+//! Typed constructors of the runtime calls of the escrow and key-value store pallets.
+
+/// Calls of `pallet_escrow`.
+pub mod escrow {
+	use node_template_runtime::{
+		pallet_escrow::{self, EscrowId, FactoryId, JobTerms, ManifestHashAlgorithm, Oracle},
+		AccountId, Balance, Call, Hash, Runtime, Signature,
+	};
+	use sp_runtime::Percent;
+
+	type EscrowCall = pallet_escrow::Call<Runtime>;
+
+	pub fn create_factory() -> Call {
+		Call::Escrow(EscrowCall::create_factory())
+	}
+
+	pub fn create(
+		manifest_url: Vec<u8>,
+		manifest_hash: Vec<u8>,
+		manifest_hash_algorithm: ManifestHashAlgorithm,
+		factory_id: FactoryId,
+		oracles: Vec<Oracle<AccountId, Balance>>,
+	) -> Call {
+		Call::Escrow(EscrowCall::create(manifest_url, manifest_hash, manifest_hash_algorithm, factory_id, oracles))
+	}
+
+	pub fn create_from_terms(
+		manifest_url: Vec<u8>,
+		manifest_hash: Vec<u8>,
+		manifest_hash_algorithm: ManifestHashAlgorithm,
+		factory_id: FactoryId,
+		oracles: Vec<Oracle<AccountId, Balance>>,
+		terms: JobTerms<Balance>,
+	) -> Call {
+		let algorithm = manifest_hash_algorithm;
+		Call::Escrow(EscrowCall::create_from_terms(manifest_url, manifest_hash, algorithm, factory_id, oracles, terms))
+	}
+
+	pub fn add_trusted_handlers(id: EscrowId, handlers: Vec<AccountId>) -> Call {
+		Call::Escrow(EscrowCall::add_trusted_handlers(id, handlers))
+	}
+
+	pub fn abort(id: EscrowId) -> Call {
+		Call::Escrow(EscrowCall::abort(id))
+	}
+
+	pub fn cancel(id: EscrowId) -> Call {
+		Call::Escrow(EscrowCall::cancel(id))
+	}
+
+	pub fn fund(id: EscrowId, amount: Balance) -> Call {
+		Call::Escrow(EscrowCall::fund(id, amount))
+	}
+
+	pub fn complete(id: EscrowId) -> Call {
+		Call::Escrow(EscrowCall::complete(id))
+	}
+
+	pub fn note_intermediate_results(id: EscrowId, url: Vec<u8>, hash: Vec<u8>) -> Call {
+		Call::Escrow(EscrowCall::note_intermediate_results(id, url, hash))
+	}
+
+	pub fn store_final_results(id: EscrowId, url: Vec<u8>, hash: Vec<u8>) -> Call {
+		Call::Escrow(EscrowCall::store_final_results(id, url, hash))
+	}
+
+	pub fn store_signed_final_results(id: EscrowId, url: Vec<u8>, hash: Vec<u8>, signature: Signature) -> Call {
+		Call::Escrow(EscrowCall::store_signed_final_results(id, url, hash, signature))
+	}
+
+	pub fn commit_results(id: EscrowId, commitment: Hash) -> Call {
+		Call::Escrow(EscrowCall::commit_results(id, commitment))
+	}
+
+	pub fn reveal_results(id: EscrowId, url: Vec<u8>, hash: Vec<u8>, salt: Vec<u8>) -> Call {
+		Call::Escrow(EscrowCall::reveal_results(id, url, hash, salt))
+	}
+
+	pub fn flag_unrevealed(id: EscrowId, committer: AccountId) -> Call {
+		Call::Escrow(EscrowCall::flag_unrevealed(id, committer))
+	}
+
+	pub fn accept_results(id: EscrowId) -> Call {
+		Call::Escrow(EscrowCall::accept_results(id))
+	}
+
+	pub fn reject_results(id: EscrowId) -> Call {
+		Call::Escrow(EscrowCall::reject_results(id))
+	}
+
+	pub fn bulk_payout(id: EscrowId, recipients: Vec<AccountId>, amounts: Vec<Balance>) -> Call {
+		Call::Escrow(EscrowCall::bulk_payout(id, recipients, amounts))
+	}
+
+	pub fn set_protocol_fee(fee: Percent) -> Call {
+		Call::Escrow(EscrowCall::set_protocol_fee(fee))
+	}
+}
+
+/// Calls of `pallet_kvstore`.
+pub mod kvstore {
+	use node_template_runtime::{
+		pallet_kvstore::{self, ValueFormat},
+		AccountId, BlockNumber, Call, Runtime,
+	};
+
+	type KVStoreCall = pallet_kvstore::Call<Runtime>;
+
+	pub fn set(key: Vec<u8>, value: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::set(key, value))
+	}
+
+	pub fn remove(key: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::remove(key))
+	}
+
+	pub fn set_many(entries: Vec<(Vec<u8>, Vec<u8>)>) -> Call {
+		Call::KVStore(KVStoreCall::set_many(entries))
+	}
+
+	pub fn grant(delegate: AccountId, key_prefix: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::grant(delegate, key_prefix))
+	}
+
+	pub fn revoke(delegate: AccountId, key_prefix: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::revoke(delegate, key_prefix))
+	}
+
+	pub fn set_for(owner: AccountId, key: Vec<u8>, value: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::set_for(owner, key, value))
+	}
+
+	pub fn remove_for(owner: AccountId, key: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::remove_for(owner, key))
+	}
+
+	pub fn set_with_ttl(key: Vec<u8>, value: Vec<u8>, ttl: BlockNumber) -> Call {
+		Call::KVStore(KVStoreCall::set_with_ttl(key, value, ttl))
+	}
+
+	pub fn register_schema(key: Vec<u8>, format: ValueFormat) -> Call {
+		Call::KVStore(KVStoreCall::register_schema(key, format))
+	}
+
+	pub fn unregister_schema(key: Vec<u8>) -> Call {
+		Call::KVStore(KVStoreCall::unregister_schema(key))
+	}
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use node_template_runtime::Hash;
+use sp_runtime::DispatchError;
+
+/// Errors of the client.
+#[derive(Debug)]
+pub enum Error {
+	/// The request to the node failed.
+	Http(reqwest::Error),
+	/// The node answered with an error.
+	Rpc { code: i64, message: String },
+	/// A response of the node could not be decoded.
+	Decode(String),
+	/// The secret URI of a signer is invalid.
+	InvalidSecretUri(String),
+	/// The extrinsic was included in a block but failed.
+	ExtrinsicFailed(DispatchError),
+	/// The extrinsic was not included or finalized within the timeout.
+	Timeout,
+	/// The block including the extrinsic was retracted before it was finalized.
+	Retracted(Hash),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Http(e) => write!(f, "Request failed: {}", e),
+			Error::Rpc { code, message } => write!(f, "RPC error {}: {}", code, message),
+			Error::Decode(e) => write!(f, "Failed to decode response: {}", e),
+			Error::InvalidSecretUri(e) => write!(f, "Invalid secret URI: {}", e),
+			Error::ExtrinsicFailed(e) => write!(f, "Extrinsic failed: {:?}", e),
+			Error::Timeout => write!(f, "Timed out waiting for the extrinsic"),
+			Error::Retracted(block) => write!(f, "Block {} was retracted", block),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::Http(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+
+impl From<reqwest::Error> for Error {
+	fn from(e: reqwest::Error) -> Self {
+		Error::Http(e)
+	}
+}
+
+impl From<serde_json::Error> for Error {
+	fn from(e: serde_json::Error) -> Self {
+		Error::Decode(e.to_string())
+	}
+}
+
+impl From<codec::Error> for Error {
+	fn from(e: codec::Error) -> Self {
+		Error::Decode(e.what().into())
+	}
+}
+
+/// Result type of the client.
+pub type Result<T> = std::result::Result<T, Error>;
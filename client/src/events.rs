@@ -0,0 +1,43 @@
+use node_template_runtime::{pallet_escrow::{self, EscrowId}, pallet_kvstore, Event, Hash, Runtime};
+
+/// An escrow event as deposited by the runtime.
+pub type EscrowEvent = pallet_escrow::Event<Runtime>;
+/// A key-value store event as deposited by the runtime.
+pub type KVStoreEvent = pallet_kvstore::Event<Runtime>;
+
+/// The events of a successful extrinsic.
+#[derive(Clone, Debug)]
+pub struct ExtrinsicEvents {
+	/// The block including the extrinsic.
+	pub block: Hash,
+	/// The index of the extrinsic in the block.
+	pub extrinsic_index: u32,
+	/// All events emitted by the extrinsic.
+	pub events: Vec<Event>,
+}
+
+impl ExtrinsicEvents {
+	/// The escrow events of the extrinsic.
+	pub fn escrow(&self) -> impl Iterator<Item = &EscrowEvent> {
+		self.events.iter().filter_map(|event| match event {
+			Event::pallet_escrow(event) => Some(event),
+			_ => None,
+		})
+	}
+
+	/// The key-value store events of the extrinsic.
+	pub fn kvstore(&self) -> impl Iterator<Item = &KVStoreEvent> {
+		self.events.iter().filter_map(|event| match event {
+			Event::pallet_kvstore(event) => Some(event),
+			_ => None,
+		})
+	}
+
+	/// The id of the escrow created by the extrinsic, if any.
+	pub fn created_escrow(&self) -> Option<EscrowId> {
+		self.escrow().find_map(|event| match event {
+			pallet_escrow::RawEvent::Pending(id, ..) => Some(*id),
+			_ => None,
+		})
+	}
+}
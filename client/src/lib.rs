@@ -0,0 +1,259 @@
+//! A client for the escrow and key-value store pallets of the node.
+//!
+//! Extrinsics are built from the typed calls in [`calls`], signed by a [`Signer`] tracking
+//! the account's nonce and submitted over the node's HTTP RPC. [`Client::submit_and_wait`]
+//! follows the chain until the extrinsic is included or finalized and returns its events.
+
+use std::{thread, time::{Duration, Instant}};
+
+use codec::{Decode, Encode};
+use log::debug;
+use frame_support::{StorageMap, StorageValue};
+use node_template_runtime::{
+	pallet_escrow::{self, EscrowId, EscrowInfo},
+	pallet_parameters, AccountId, Balance, BlockNumber, Call, Event, Hash, Header, Index, Moment, Runtime,
+	SignedBlock, SignedExtra, UncheckedExtrinsic,
+};
+use serde::Deserialize;
+use serde_json::json;
+use sp_core::{crypto::Pair as _, hashing::twox_128, Bytes};
+use sp_runtime::{
+	generic::{Era, Phase, SignedPayload},
+	traits::{BlakeTwo256, Hash as _},
+};
+
+pub mod calls;
+mod error;
+mod events;
+pub mod rpc;
+mod signer;
+
+pub use error::{Error, Result};
+pub use events::{EscrowEvent, ExtrinsicEvents, KVStoreEvent};
+pub use signer::Signer;
+
+type EventRecord = frame_system::EventRecord<Event, Hash>;
+type AccountInfo = frame_system::AccountInfo<Index, pallet_balances::AccountData<Balance>>;
+
+/// How long [`Client::submit_and_wait`] waits by default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Until when [`Client::submit_and_wait`] waits for an extrinsic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitFor {
+	/// Until the extrinsic is included in a best block.
+	InBlock,
+	/// Until the block including the extrinsic is finalized.
+	Finalized,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeVersion {
+	spec_version: u32,
+	transaction_version: u32,
+}
+
+/// A connection to a node.
+pub struct Client {
+	rpc: rpc::Rpc,
+	genesis_hash: Hash,
+	timeout: Duration,
+}
+
+impl Client {
+	/// Connect to the HTTP RPC endpoint of a node at `url`, e.g. `http://localhost:9933`.
+	pub fn connect(url: impl Into<String>) -> Result<Self> {
+		let rpc = rpc::Rpc::new(url);
+		let genesis_hash = rpc.request::<Option<Hash>>("chain_getBlockHash", json!([0]))?
+			.ok_or_else(|| Error::Decode("Missing genesis block".into()))?;
+		Ok(Self { rpc, genesis_hash, timeout: DEFAULT_TIMEOUT })
+	}
+
+	/// Wait at most `timeout` in [`Client::submit_and_wait`].
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// The underlying RPC client, for requests not wrapped by the client.
+	pub fn rpc(&self) -> &rpc::Rpc {
+		&self.rpc
+	}
+
+	/// The hash of the genesis block of the chain.
+	pub fn genesis_hash(&self) -> Hash {
+		self.genesis_hash
+	}
+
+	/// The next nonce of `account`, including the transactions in the pool.
+	pub fn next_nonce(&self, account: &AccountId) -> Result<Index> {
+		self.rpc.request("system_accountNextIndex", json!([account]))
+	}
+
+	/// Sign `call` by `signer` with the given `nonce`.
+	pub fn sign(&self, signer: &Signer, call: Call, nonce: Index) -> Result<UncheckedExtrinsic> {
+		let version: RuntimeVersion = self.rpc.request("state_getRuntimeVersion", json!([]))?;
+		let extra: SignedExtra = (
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(Era::Immortal),
+			frame_system::CheckNonce::from(nonce),
+			frame_system::CheckWeight::new(),
+		);
+		let additional = (
+			version.spec_version,
+			version.transaction_version,
+			self.genesis_hash,
+			self.genesis_hash,
+			(),
+			(),
+		);
+		let payload = SignedPayload::from_raw(call, extra, additional);
+		let signature = payload.using_encoded(|payload| signer.pair().sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+		Ok(UncheckedExtrinsic::new_signed(call, signer.account().clone(), signature.into(), extra))
+	}
+
+	/// Sign `call` by `signer` and submit it to the transaction pool.
+	///
+	/// Returns the hash of the extrinsic.
+	pub fn submit(&self, signer: &Signer, call: Call) -> Result<Hash> {
+		signer.with_nonce(
+			|| self.next_nonce(signer.account()),
+			|nonce| {
+				let extrinsic = self.sign(signer, call, nonce)?;
+				self.rpc.request("author_submitExtrinsic", json!([Bytes(extrinsic.encode())]))
+			},
+		)
+	}
+
+	/// Sign and submit `call`, then wait until it is included or finalized as given by `wait`.
+	///
+	/// Returns the events of the extrinsic, or [`Error::ExtrinsicFailed`] if it failed.
+	pub fn submit_and_wait(&self, signer: &Signer, call: Call, wait: WaitFor) -> Result<ExtrinsicEvents> {
+		let deadline = Instant::now() + self.timeout;
+		let start = self.best_number()?;
+		let hash = self.submit(signer, call)?;
+		debug!("Submitted extrinsic {:?}", hash);
+
+		let (number, block, index) = self.find_extrinsic(hash, start + 1, deadline)?;
+		if wait == WaitFor::Finalized {
+			self.wait_finalized(number, block, deadline)?;
+		}
+
+		let mut events = Vec::new();
+		for record in self.events(block)? {
+			if record.phase != Phase::ApplyExtrinsic(index) {
+				continue;
+			}
+			if let Event::frame_system(frame_system::RawEvent::ExtrinsicFailed(error, _)) = record.event {
+				return Err(Error::ExtrinsicFailed(error));
+			}
+			events.push(record.event);
+		}
+		Ok(ExtrinsicEvents { block, extrinsic_index: index, events })
+	}
+
+	/// The value stored by `owner` under `key` at the block `at`, the best block if `None`.
+	pub fn kvstore_get(&self, owner: &AccountId, key: &[u8], at: Option<Hash>) -> Result<Option<Vec<u8>>> {
+		let value: Option<Bytes> = self.rpc.request("kvstore_get", json!([owner, Bytes(key.to_vec()), at]))?;
+		Ok(value.map(|value| value.0))
+	}
+
+	/// The escrow `id` at the best block, `None` if it does not exist or was aborted.
+	pub fn escrow(&self, id: EscrowId) -> Result<Option<EscrowInfo<Moment, AccountId, Balance>>> {
+		self.storage(&pallet_escrow::Escrows::<Runtime>::hashed_key_for(id), None)
+	}
+
+	/// Whether the manifest of escrow `id` matched its hash, `None` if it was not checked (yet).
+	pub fn manifest_check(&self, id: EscrowId) -> Result<Option<bool>> {
+		self.storage(&pallet_escrow::ManifestChecks::hashed_key_for(id), None)
+	}
+
+	/// The free balance of `account` at the best block.
+	pub fn free_balance(&self, account: &AccountId) -> Result<Balance> {
+		let key = frame_system::Account::<Runtime>::hashed_key_for(account);
+		let info: Option<AccountInfo> = self.storage(&key, None)?;
+		Ok(info.map_or(0, |info| info.data.free))
+	}
+
+	/// The maximum number of recipients of a bulk payout.
+	pub fn bulk_accounts_limit(&self) -> Result<u32> {
+		let limit = self.storage(&pallet_parameters::BulkAccountsLimit::hashed_key(), None)?;
+		Ok(limit.unwrap_or_default())
+	}
+
+	/// The events deposited in the block `at`.
+	///
+	/// The key is built by hand as the storage type of `System::Events` is private to `frame_system`.
+	pub fn events(&self, at: Hash) -> Result<Vec<EventRecord>> {
+		let events = self.storage(&[twox_128(b"System"), twox_128(b"Events")].concat(), Some(at))?;
+		Ok(events.unwrap_or_default())
+	}
+
+	/// The value under the storage `key` at the block `at`, the best block if `None`.
+	pub fn storage<T: Decode>(&self, key: &[u8], at: Option<Hash>) -> Result<Option<T>> {
+		let data: Option<Bytes> = self.rpc.request("state_getStorage", json!([Bytes(key.to_vec()), at]))?;
+		match data {
+			Some(data) => Ok(Some(Decode::decode(&mut &data.0[..])?)),
+			None => Ok(None),
+		}
+	}
+
+	fn best_number(&self) -> Result<BlockNumber> {
+		let header: Header = self.rpc.request("chain_getHeader", json!([]))?;
+		Ok(header.number)
+	}
+
+	fn block_hash(&self, number: BlockNumber) -> Result<Option<Hash>> {
+		self.rpc.request("chain_getBlockHash", json!([number]))
+	}
+
+	/// Follow the best chain from block `from` until a block includes the extrinsic `hash`.
+	///
+	/// Returns the number and hash of the block and the index of the extrinsic.
+	fn find_extrinsic(&self, hash: Hash, mut from: BlockNumber, deadline: Instant) -> Result<(BlockNumber, Hash, u32)> {
+		loop {
+			let best = self.best_number()?;
+			while from <= best {
+				let block_hash = match self.block_hash(from)? {
+					Some(block_hash) => block_hash,
+					// Reorganized in the meantime, try again.
+					None => break,
+				};
+				let block: Option<SignedBlock> = self.rpc.request("chain_getBlock", json!([block_hash]))?;
+				let extrinsics = block.map(|block| block.block.extrinsics).unwrap_or_default();
+				if let Some(index) = extrinsics.iter().position(|xt| BlakeTwo256::hash_of(xt) == hash) {
+					return Ok((from, block_hash, index as u32));
+				}
+				from += 1;
+			}
+			if Instant::now() >= deadline {
+				return Err(Error::Timeout);
+			}
+			thread::sleep(POLL_INTERVAL);
+		}
+	}
+
+	/// Wait until the block `number` is finalized and check that it is `hash`.
+	fn wait_finalized(&self, number: BlockNumber, hash: Hash, deadline: Instant) -> Result<()> {
+		loop {
+			let finalized: Hash = self.rpc.request("chain_getFinalizedHead", json!([]))?;
+			let header: Header = self.rpc.request("chain_getHeader", json!([finalized]))?;
+			if header.number >= number {
+				return match self.block_hash(number)? {
+					Some(canonical) if canonical == hash => Ok(()),
+					_ => Err(Error::Retracted(hash)),
+				};
+			}
+			if Instant::now() >= deadline {
+				return Err(Error::Timeout);
+			}
+			thread::sleep(POLL_INTERVAL);
+		}
+	}
+}
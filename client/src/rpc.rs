@@ -0,0 +1,46 @@
+//! A minimal blocking JSON-RPC client over HTTP.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{Error, Result};
+
+#[derive(Deserialize)]
+struct Response {
+	#[serde(default)]
+	result: Value,
+	error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+	code: i64,
+	message: String,
+}
+
+/// Sends JSON-RPC requests to a node.
+pub struct Rpc {
+	http: reqwest::blocking::Client,
+	url: String,
+	next_id: AtomicU64,
+}
+
+impl Rpc {
+	/// Create a client sending requests to the HTTP RPC endpoint at `url`.
+	pub fn new(url: impl Into<String>) -> Self {
+		Self { http: reqwest::blocking::Client::new(), url: url.into(), next_id: AtomicU64::new(1) }
+	}
+
+	/// Call `method` with `params`, an array of positional parameters.
+	pub fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+		let response: Response = self.http.post(&self.url).json(&body).send()?.error_for_status()?.json()?;
+		if let Some(ResponseError { code, message }) = response.error {
+			return Err(Error::Rpc { code, message });
+		}
+		Ok(serde_json::from_value(response.result)?)
+	}
+}
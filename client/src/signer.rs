@@ -0,0 +1,64 @@
+use std::sync::Mutex;
+
+use node_template_runtime::{AccountId, Index};
+use sp_core::{crypto::Pair as _, sr25519};
+use sp_runtime::{traits::IdentifyAccount, MultiSigner};
+
+use crate::{Error, Result};
+
+/// Signs extrinsics for an account and tracks the account's nonce.
+pub struct Signer {
+	pair: sr25519::Pair,
+	account: AccountId,
+	nonce: Mutex<Option<Index>>,
+}
+
+impl Signer {
+	/// Create a signer for the account of `pair`.
+	pub fn new(pair: sr25519::Pair) -> Self {
+		let account = MultiSigner::from(pair.public()).into_account();
+		Self { pair, account, nonce: Mutex::new(None) }
+	}
+
+	/// Create a signer from a secret URI, e.g. `//Alice` or a mnemonic.
+	pub fn from_uri(uri: &str) -> Result<Self> {
+		let pair = sr25519::Pair::from_string(uri, None)
+			.map_err(|e| Error::InvalidSecretUri(format!("{:?}", e)))?;
+		Ok(Self::new(pair))
+	}
+
+	/// The account signing the extrinsics.
+	pub fn account(&self) -> &AccountId {
+		&self.account
+	}
+
+	pub(crate) fn pair(&self) -> &sr25519::Pair {
+		&self.pair
+	}
+
+	/// Run `f` with the next nonce of the account, fetched with `fetch` if unknown.
+	///
+	/// The nonce is incremented if `f` succeeds. Otherwise it is fetched again for the next
+	/// extrinsic, as the node may or may not have accepted the failed one.
+	pub(crate) fn with_nonce<T>(
+		&self,
+		fetch: impl FnOnce() -> Result<Index>,
+		f: impl FnOnce(Index) -> Result<T>,
+	) -> Result<T> {
+		let mut cached = self.nonce.lock().expect("nonce lock is not poisoned");
+		let nonce = match *cached {
+			Some(nonce) => nonce,
+			None => fetch()?,
+		};
+		match f(nonce) {
+			Ok(result) => {
+				*cached = Some(nonce + 1);
+				Ok(result)
+			}
+			Err(e) => {
+				*cached = None;
+				Err(e)
+			}
+		}
+	}
+}
@@ -0,0 +1,148 @@
+//! Runs the client against a `--dev --tmp` node started in-process with instant sealing.
+
+use std::{thread, time::Duration};
+
+use futures::FutureExt;
+use node_template::{cli::{Cli, Sealing}, service};
+use node_template_client::{calls, Client, Error, Signer, WaitFor};
+use node_template_runtime::{
+	pallet_escrow::{self, FixedFeeTiming, ManifestHashAlgorithm, Oracle, OracleRole},
+	pallet_kvstore, AccountId, Balance,
+};
+use sc_cli::CliConfiguration;
+use sc_service::{TaskManager, TaskType};
+use sp_runtime::Percent;
+use structopt::StructOpt;
+
+struct DevNode {
+	url: String,
+	// Dropped before the runtime the node's tasks are running on.
+	_task_manager: TaskManager,
+	_runtime: tokio::runtime::Runtime,
+}
+
+fn start_dev_node(rpc_port: u16) -> DevNode {
+	let rpc_port = rpc_port.to_string();
+	let ws_port = (rpc_port.parse::<u16>().unwrap() + 1).to_string();
+	let p2p_port = (rpc_port.parse::<u16>().unwrap() + 2).to_string();
+	let cli = Cli::from_iter(&[
+		"node-template", "--dev", "--tmp",
+		"--rpc-port", &rpc_port, "--ws-port", &ws_port, "--port", &p2p_port,
+	]);
+
+	let runtime = tokio::runtime::Builder::new()
+		.threaded_scheduler()
+		.enable_all()
+		.build()
+		.unwrap();
+	let handle = runtime.handle().clone();
+	let task_executor = move |fut, task_type| match task_type {
+		TaskType::Async => handle.spawn(fut).map(drop),
+		TaskType::Blocking => handle.spawn_blocking(move || futures::executor::block_on(fut)).map(drop),
+	};
+	let config = cli.run.create_configuration(&cli, task_executor.into()).unwrap();
+	let task_manager = runtime.enter(|| service::new_manual_seal(config, Sealing::Instant)).unwrap();
+
+	DevNode { url: format!("http://127.0.0.1:{}", rpc_port), _task_manager: task_manager, _runtime: runtime }
+}
+
+fn connect(node: &DevNode) -> Client {
+	for _ in 0..50 {
+		if let Ok(client) = Client::connect(node.url.clone()) {
+			return client.with_timeout(Duration::from_secs(20));
+		}
+		thread::sleep(Duration::from_millis(100));
+	}
+	panic!("Node at {} did not start", node.url);
+}
+
+fn oracle(signer: &Signer, role: OracleRole) -> Oracle<AccountId, Balance> {
+	Oracle {
+		account: signer.account().clone(),
+		role,
+		fee: Percent::from_percent(10),
+		fixed_fee: 0,
+		fixed_fee_timing: FixedFeeTiming::Completion,
+	}
+}
+
+#[test]
+fn creates_and_funds_escrow() {
+	let node = start_dev_node(19933);
+	let client = connect(&node);
+	let alice = Signer::from_uri("//Alice").unwrap();
+	let bob = Signer::from_uri("//Bob").unwrap();
+	let charlie = Signer::from_uri("//Charlie").unwrap();
+
+	// Reputation and Recording oracles have to publish a public key first.
+	for oracle in &[&bob, &charlie] {
+		let key: &[u8] = oracle.account().as_ref();
+		let events = client.submit_and_wait(
+			oracle,
+			calls::kvstore::set(pallet_kvstore::PUBLIC_KEY.to_vec(), key.to_vec()),
+			WaitFor::Finalized,
+		).unwrap();
+		assert_eq!(events.kvstore().count(), 1);
+		assert_eq!(client.kvstore_get(oracle.account(), pallet_kvstore::PUBLIC_KEY, None).unwrap(), Some(key.to_vec()));
+	}
+
+	let events = client.submit_and_wait(&alice, calls::escrow::create_factory(), WaitFor::InBlock).unwrap();
+	let factory_id = match events.escrow().next() {
+		Some(pallet_escrow::RawEvent::FactoryCreated(id, who)) => {
+			assert_eq!(who, alice.account());
+			*id
+		}
+		event => panic!("Unexpected event {:?}", event),
+	};
+
+	let oracles = vec![oracle(&bob, OracleRole::Reputation), oracle(&charlie, OracleRole::Recording)];
+	let events = client.submit_and_wait(
+		&alice,
+		calls::escrow::create(
+			b"http://example.com/manifest.json".to_vec(),
+			b"manifest-hash".to_vec(),
+			ManifestHashAlgorithm::Other,
+			factory_id,
+			oracles.clone(),
+		),
+		WaitFor::Finalized,
+	).unwrap();
+	let id = events.created_escrow().expect("escrow was created");
+	match events.escrow().next() {
+		Some(pallet_escrow::RawEvent::Pending(_, creator, _, _, _, created_oracles)) => {
+			assert_eq!(creator, alice.account());
+			assert_eq!(created_oracles, &oracles);
+		}
+		event => panic!("Unexpected event {:?}", event),
+	}
+
+	let events = client.submit_and_wait(&alice, calls::escrow::fund(id, 1_000), WaitFor::InBlock).unwrap();
+	assert!(events.escrow().any(|event| *event == pallet_escrow::RawEvent::Funded(id, alice.account().clone(), 1_000)));
+}
+
+#[test]
+fn reports_failed_extrinsics_and_tracks_nonces() {
+	let node = start_dev_node(19953);
+	let client = connect(&node);
+	let alice = Signer::from_uri("//Alice").unwrap();
+
+	// Several extrinsics are pending in the pool at the same time.
+	let hashes: Vec<_> = (0..3u8)
+		.map(|i| client.submit(&alice, calls::kvstore::set(vec![i], vec![i])).unwrap())
+		.collect();
+	assert_eq!(hashes.len(), 3);
+	let events = client.submit_and_wait(&alice, calls::kvstore::set(b"key".to_vec(), b"value".to_vec()), WaitFor::InBlock).unwrap();
+	assert_eq!(client.kvstore_get(alice.account(), b"key", Some(events.block)).unwrap(), Some(b"value".to_vec()));
+	for i in 0..3u8 {
+		assert_eq!(client.kvstore_get(alice.account(), &[i], None).unwrap(), Some(vec![i]));
+	}
+
+	// An unknown escrow fails on-chain, but the nonce is used nevertheless.
+	match client.submit_and_wait(&alice, calls::escrow::fund(1_000, 1), WaitFor::InBlock) {
+		Err(Error::ExtrinsicFailed(_)) => {}
+		result => panic!("Unexpected result {:?}", result.map(|events| events.events)),
+	}
+	assert_eq!(client.next_nonce(alice.account()).unwrap(), 5);
+	client.submit_and_wait(&alice, calls::kvstore::remove(b"key".to_vec()), WaitFor::InBlock).unwrap();
+	assert_eq!(client.kvstore_get(alice.account(), b"key", None).unwrap(), None);
+}
@@ -1,5 +1,14 @@
 const should = require('should')
 const { createType } = require('@polkadot/types');
+const { blake2AsHex } = require('@polkadot/util-crypto');
+
+// The events of the latest block emitted by the kvStore pallet.
+async function kvStoreEvents() {
+	const events = await halva.polkadot.query.system.events()
+	return events
+		.map(({ event }) => event)
+		.filter((event) => event.section === 'kvStore')
+}
 
 describe('kvStore tests', () => {
 	const davePair = {address: "5DAAnrj7VHTznn2AWBemMuyBwZWs6FNFjdyVXUeYum3PTXFy"}
@@ -18,4 +27,30 @@ describe('kvStore tests', () => {
 
 		assert.deepEqual(storageValue, expectedArr, 'storage value should be set correctly')
 	})
-})
\ No newline at end of file
+
+	it("should emit the hash and length of stored values", async () => {
+		const key = [1,2,3]
+		const value = [7,8,9,10]
+		const tx = await halva.polkadot.tx.kvStore.set(key, value)
+		await passes(tx, 'set', alicePair)
+
+		const [event] = await kvStoreEvents()
+		assert.equal(event.method, 'StoredHash', 'values should not be part of the event')
+		const [account, eventKey, hash, length] = event.data
+		assert.equal(account.toString(), alicePair.address)
+		assert.equal(eventKey.toHex(), halva.polkadot.createType('Bytes', key).toHex())
+		assert.equal(hash.toHex(), blake2AsHex(Uint8Array.from(value), 256), 'event should carry the blake2_256 hash')
+		assert.equal(length.toNumber(), value.length, 'event should carry the value length')
+	})
+
+	it("should remove the values", async () => {
+		const key = [1,2,3]
+		const tx = await halva.polkadot.tx.kvStore.remove(key)
+		await passes(tx, 'remove', alicePair)
+
+		const [event] = await kvStoreEvents()
+		assert.equal(event.method, 'Removed')
+		const storageValue = await halva.polkadot.query.kvStore.storage(alicePair.address, key)
+		assert.equal(storageValue.length, 0, 'storage value should be removed')
+	})
+})
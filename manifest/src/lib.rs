@@ -0,0 +1,297 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! The job manifest an escrow refers to by `manifest_url` and `manifest_hash`.
+//!
+//! [`Manifest`] models the JSON format of `example-manifest.json`, [`Manifest::validate`]
+//! checks its rules and [`hash`] computes the `manifest_hash` stored by `escrow.create`.
+//! The hash covers the canonical form of the JSON document, so reformatting the manifest,
+//! reordering its keys or writing its numbers differently does not change it. Unknown fields are kept in the hash.
+
+extern crate alloc;
+
+use alloc::{collections::BTreeMap, string::String};
+use core::fmt::{self, Write};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The kinds of tasks a job can consist of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestType {
+	ImageLabelBinary,
+	ImageLabelMultipleChoice,
+	TextFreeEntry,
+	TextMultipleChoiceOneOption,
+	TextMultipleChoiceMultipleOptions,
+	ImageLabelAreaAdjust,
+	ImageLabelAreaSelect,
+	ImageLabelSinglePolygon,
+	ImageLabelMultiplePolygons,
+	ImageLabelSemanticSegmentationOneOption,
+	ImageLabelSemanticSegmentationMultipleOptions,
+	ImageLabelText,
+	MultiChallenge,
+}
+
+/// How the tasks of a job are handed out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobMode {
+	Batch,
+	Online,
+	InstantDelivery,
+}
+
+/// A job manifest.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+	pub job_mode: JobMode,
+	pub request_type: RequestType,
+	/// The question shown to workers by language code.
+	pub requester_question: BTreeMap<String, String>,
+	#[serde(default)]
+	pub requester_question_example: Vec<String>,
+	/// The possible answers, if restricted, by answer key and language code.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub requester_restricted_answer_set: Option<BTreeMap<String, BTreeMap<String, String>>>,
+	/// The fraction of answers that have to match the ground truth.
+	pub requester_accuracy_target: f64,
+	/// The minimum number of answers per task.
+	pub requester_min_repeats: u32,
+	/// The maximum number of answers per task.
+	pub requester_max_repeats: u32,
+	/// The price paid per task as a decimal number of tokens, e.g. `"0.000064"`.
+	pub task_bid_price: String,
+	pub job_total_tasks: u64,
+	/// The fraction of every payout the oracles receive.
+	pub oracle_stake: f64,
+	pub recording_oracle_addr: String,
+	pub reputation_oracle_addr: String,
+	pub reputation_agent_addr: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub taskdata_uri: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub groundtruth_uri: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub confcalc_configuration_id: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub restricted_audience: Option<Value>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub request_config: Option<Value>,
+}
+
+/// A manifest that breaks one of the rules of [`Manifest::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+	/// `oracle_stake` is not within 0 and 1.
+	OracleStakeOutOfBounds,
+	/// `requester_accuracy_target` is not within 0 and 1.
+	AccuracyTargetOutOfBounds,
+	/// `requester_min_repeats` is 0 or greater than `requester_max_repeats`.
+	InvalidRepeats,
+	/// `job_total_tasks` is 0.
+	NoTasks,
+	/// `task_bid_price` is not a positive decimal number.
+	InvalidTaskBidPrice,
+	/// `requester_question` has no language.
+	MissingQuestion,
+	/// An oracle address is empty.
+	MissingOracleAddress,
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let message = match self {
+			ValidationError::OracleStakeOutOfBounds => "oracle_stake must be within 0 and 1",
+			ValidationError::AccuracyTargetOutOfBounds => "requester_accuracy_target must be within 0 and 1",
+			ValidationError::InvalidRepeats =>
+				"requester_min_repeats must be positive and at most requester_max_repeats",
+			ValidationError::NoTasks => "job_total_tasks must be positive",
+			ValidationError::InvalidTaskBidPrice => "task_bid_price must be a positive decimal number",
+			ValidationError::MissingQuestion => "requester_question must have at least one language",
+			ValidationError::MissingOracleAddress => "oracle addresses must not be empty",
+		};
+		f.write_str(message)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+/// Errors reading a manifest.
+#[derive(Debug)]
+pub enum Error {
+	/// The manifest is not JSON or does not match the format.
+	Parse(serde_json::Error),
+	/// The manifest is well-formed but invalid.
+	Invalid(ValidationError),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Parse(e) => write!(f, "Malformed manifest: {}", e),
+			Error::Invalid(e) => write!(f, "Invalid manifest: {}", e),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl Manifest {
+	/// Parse and validate a manifest.
+	pub fn from_slice(json: &[u8]) -> Result<Self, Error> {
+		let manifest: Manifest = serde_json::from_slice(json).map_err(Error::Parse)?;
+		manifest.validate().map_err(Error::Invalid)?;
+		Ok(manifest)
+	}
+
+	/// Check the rules a manifest has to follow beyond its format.
+	pub fn validate(&self) -> Result<(), ValidationError> {
+		let is_fraction = |value: f64| (0.0..=1.0).contains(&value);
+		if !is_fraction(self.oracle_stake) {
+			return Err(ValidationError::OracleStakeOutOfBounds);
+		}
+		if !is_fraction(self.requester_accuracy_target) {
+			return Err(ValidationError::AccuracyTargetOutOfBounds);
+		}
+		if self.requester_min_repeats == 0 || self.requester_min_repeats > self.requester_max_repeats {
+			return Err(ValidationError::InvalidRepeats);
+		}
+		if self.job_total_tasks == 0 {
+			return Err(ValidationError::NoTasks);
+		}
+		// Checked at the finest precision, so any positive price is accepted.
+		let price = parse_decimal(&self.task_bid_price, u32::max_value())
+			.ok_or(ValidationError::InvalidTaskBidPrice)?;
+		if price.integer.iter().chain(price.fraction.iter()).all(|digit| *digit == 0) {
+			return Err(ValidationError::InvalidTaskBidPrice);
+		}
+		if self.requester_question.is_empty() {
+			return Err(ValidationError::MissingQuestion);
+		}
+		let addresses = [&self.recording_oracle_addr, &self.reputation_oracle_addr, &self.reputation_agent_addr];
+		if addresses.iter().any(|address| address.trim().is_empty()) {
+			return Err(ValidationError::MissingOracleAddress);
+		}
+		Ok(())
+	}
+
+	/// `task_bid_price` in the smallest unit of a token with `decimals` decimal places.
+	///
+	/// `None` if the price is not a decimal number, is more precise than the token or overflows.
+	pub fn task_bid_price_units(&self, decimals: u32) -> Option<u128> {
+		let Decimal { integer, fraction } = parse_decimal(&self.task_bid_price, decimals)?;
+		let padding = (decimals as usize).checked_sub(fraction.len())?;
+		integer.iter()
+			.chain(fraction.iter())
+			.chain(sp_std::iter::repeat(&0).take(padding))
+			.try_fold(0u128, |units, digit| units.checked_mul(10)?.checked_add(*digit as u128))
+	}
+
+	/// The budget of the job, `task_bid_price` times `job_total_tasks`, in the smallest unit of a
+	/// token with `decimals` decimal places.
+	pub fn total_budget_units(&self, decimals: u32) -> Option<u128> {
+		self.task_bid_price_units(decimals)?.checked_mul(self.job_total_tasks as u128)
+	}
+}
+
+struct Decimal {
+	integer: Vec<u8>,
+	fraction: Vec<u8>,
+}
+
+/// Parse an unsigned decimal number with at most `max_fraction` significant fractional digits.
+fn parse_decimal(text: &str, max_fraction: u32) -> Option<Decimal> {
+	let digits = |s: &str| s.bytes()
+		.map(|b| if b.is_ascii_digit() { Some(b - b'0') } else { None })
+		.collect::<Option<Vec<u8>>>();
+	let (integer, fraction) = match text.find('.') {
+		Some(dot) => (&text[..dot], &text[dot + 1..]),
+		None => (text, ""),
+	};
+	if integer.is_empty() || (text.contains('.') && fraction.is_empty()) {
+		return None;
+	}
+	let integer = digits(integer)?;
+	let mut fraction = digits(fraction)?;
+	while fraction.last() == Some(&0) {
+		fraction.pop();
+	}
+	if fraction.len() as u64 > max_fraction as u64 {
+		return None;
+	}
+	Some(Decimal { integer, fraction })
+}
+
+/// The canonical form of a JSON document: keys sorted, no insignificant whitespace,
+/// strings escaped as by `serde_json` and numbers normalised, see [`write_number`].
+pub fn canonical_json(json: &[u8]) -> Result<Vec<u8>, serde_json::Error> {
+	let value: Value = serde_json::from_slice(json)?;
+	let mut canonical = String::new();
+	write_canonical(&mut canonical, &value).expect("writing to a string does not fail");
+	Ok(canonical.into_bytes())
+}
+
+fn write_canonical(out: &mut String, value: &Value) -> fmt::Result {
+	match value {
+		Value::Null => out.write_str("null"),
+		Value::Bool(b) => write!(out, "{}", b),
+		Value::Number(n) => write_number(out, n),
+		Value::String(s) => write_string(out, s),
+		Value::Array(values) => {
+			out.write_char('[')?;
+			for (i, value) in values.iter().enumerate() {
+				if i > 0 {
+					out.write_char(',')?;
+				}
+				write_canonical(out, value)?;
+			}
+			out.write_char(']')
+		}
+		Value::Object(map) => {
+			let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+			entries.sort_by(|a, b| a.0.cmp(b.0));
+			out.write_char('{')?;
+			for (i, (key, value)) in entries.into_iter().enumerate() {
+				if i > 0 {
+					out.write_char(',')?;
+				}
+				write_string(out, key)?;
+				out.write_char(':')?;
+				write_canonical(out, value)?;
+			}
+			out.write_char('}')
+		}
+	}
+}
+
+/// Write integral numbers without fraction or exponent, so `1`, `1.0` and `1e0` are written as `1`,
+/// and other numbers in the shortest form reading back to the same `f64`.
+fn write_number(out: &mut String, n: &serde_json::Number) -> fmt::Result {
+	if n.is_u64() || n.is_i64() {
+		return write!(out, "{}", n);
+	}
+	let f = n.as_f64().expect("numbers that are not integers are floats; qed");
+	// Integers beyond `u64` are parsed as floats too, `1e38` keeps the cast within `i128`.
+	if f.abs() < 1e38 && f == (f as i128) as f64 {
+		write!(out, "{}", f as i128)
+	} else {
+		write!(out, "{}", n)
+	}
+}
+
+fn write_string(out: &mut String, s: &str) -> fmt::Result {
+	let escaped = serde_json::to_string(s).map_err(|_| fmt::Error)?;
+	out.write_str(&escaped)
+}
+
+/// The `manifest_hash` of a manifest: the Blake2-256 hash of its canonical JSON form.
+pub fn hash(json: &[u8]) -> Result<[u8; 32], serde_json::Error> {
+	Ok(sp_io::hashing::blake2_256(&canonical_json(json)?))
+}
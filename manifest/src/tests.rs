@@ -0,0 +1,98 @@
+use crate::*;
+
+const EXAMPLE: &str = include_str!("../../example-manifest.json");
+
+fn example() -> Manifest {
+	Manifest::from_slice(EXAMPLE.as_bytes()).unwrap()
+}
+
+#[test]
+fn parses_example_manifest() {
+	let manifest = example();
+	assert_eq!(manifest.request_type, RequestType::ImageLabelAreaSelect);
+	assert_eq!(manifest.job_mode, JobMode::Batch);
+	assert_eq!((manifest.requester_min_repeats, manifest.requester_max_repeats), (4, 5));
+	assert_eq!(manifest.job_total_tasks, 750);
+	assert!((manifest.oracle_stake - 0.05).abs() < f64::EPSILON);
+	assert_eq!(manifest.requester_question["en"], "Please click on the eyes and mouth in each image.");
+	assert_eq!(manifest.requester_restricted_answer_set.unwrap()["0"]["en"], "nose_eyes");
+}
+
+#[test]
+fn validation_rules() {
+	let invalid = |change: fn(&mut Manifest)| {
+		let mut manifest = example();
+		change(&mut manifest);
+		manifest.validate().unwrap_err()
+	};
+	assert_eq!(invalid(|m| m.oracle_stake = 1.5), ValidationError::OracleStakeOutOfBounds);
+	assert_eq!(invalid(|m| m.oracle_stake = -0.1), ValidationError::OracleStakeOutOfBounds);
+	assert_eq!(invalid(|m| m.requester_accuracy_target = 2.0), ValidationError::AccuracyTargetOutOfBounds);
+	assert_eq!(invalid(|m| m.requester_min_repeats = 6), ValidationError::InvalidRepeats);
+	assert_eq!(invalid(|m| m.requester_min_repeats = 0), ValidationError::InvalidRepeats);
+	assert_eq!(invalid(|m| m.job_total_tasks = 0), ValidationError::NoTasks);
+	assert_eq!(invalid(|m| m.task_bid_price = "0.000".into()), ValidationError::InvalidTaskBidPrice);
+	assert_eq!(invalid(|m| m.task_bid_price = "-1".into()), ValidationError::InvalidTaskBidPrice);
+	assert_eq!(invalid(|m| m.task_bid_price = "1e-5".into()), ValidationError::InvalidTaskBidPrice);
+	assert_eq!(invalid(|m| m.requester_question.clear()), ValidationError::MissingQuestion);
+	assert_eq!(invalid(|m| m.reputation_oracle_addr.clear()), ValidationError::MissingOracleAddress);
+
+	// Unknown request types are rejected when parsing.
+	let unknown = EXAMPLE.replace("image_label_area_select", "image_label_teleport");
+	assert!(matches!(Manifest::from_slice(unknown.as_bytes()), Err(Error::Parse(_))));
+	let invalid = EXAMPLE.replace("\"requester_min_repeats\": 4", "\"requester_min_repeats\": 9");
+	assert!(matches!(Manifest::from_slice(invalid.as_bytes()), Err(Error::Invalid(ValidationError::InvalidRepeats))));
+}
+
+#[test]
+fn task_bid_price_in_units() {
+	let mut manifest = example();
+	assert_eq!(manifest.task_bid_price_units(12), Some(64_000_000));
+	assert_eq!(manifest.task_bid_price_units(6), Some(64));
+	assert_eq!(manifest.task_bid_price_units(5), None);
+	assert_eq!(manifest.total_budget_units(6), Some(64 * 750));
+
+	manifest.task_bid_price = "12.50".into();
+	assert_eq!(manifest.task_bid_price_units(1), Some(125));
+	assert_eq!(manifest.task_bid_price_units(0), None);
+	manifest.task_bid_price = "3".into();
+	assert_eq!(manifest.task_bid_price_units(0), Some(3));
+	manifest.task_bid_price = "1.".into();
+	assert_eq!(manifest.task_bid_price_units(2), None);
+}
+
+#[test]
+fn canonical_form() {
+	assert_eq!(
+		canonical_json(r#" { "b": [1, 2.5, "xé"], "a": {"d": null, "c": true} } "#.as_bytes()).unwrap(),
+		r#"{"a":{"c":true,"d":null},"b":[1,2.5,"xé"]}"#.as_bytes(),
+	);
+	assert!(canonical_json(b"{").is_err());
+}
+
+#[test]
+fn canonical_numbers() {
+	for json in ["1", "1.0", "1e0", "10E-1", "1.000"].iter() {
+		assert_eq!(canonical_json(json.as_bytes()).unwrap(), b"1", "{}", json);
+	}
+	for json in ["2.5", "2.50", "25e-1", "0.25e1"].iter() {
+		assert_eq!(canonical_json(json.as_bytes()).unwrap(), b"2.5", "{}", json);
+	}
+	assert_eq!(canonical_json(b"-0.0").unwrap(), b"0");
+	assert_eq!(canonical_json(b"-3e2").unwrap(), b"-300");
+	assert_eq!(canonical_json(b"1e20").unwrap(), b"100000000000000000000");
+	assert_eq!(canonical_json(b"100000000000000000000").unwrap(), b"100000000000000000000");
+	assert_eq!(hash(br#"{"price": 1}"#).unwrap(), hash(br#"{"price": 1.0}"#).unwrap());
+	assert_eq!(hash(br#"{"price": 1}"#).unwrap(), hash(br#"{"price": 1e0}"#).unwrap());
+}
+
+#[test]
+fn hash_ignores_formatting() {
+	let expected = hash(EXAMPLE.as_bytes()).unwrap();
+	let compact = serde_json::to_vec(&serde_json::from_str::<serde_json::Value>(EXAMPLE).unwrap()).unwrap();
+	assert_eq!(hash(&compact).unwrap(), expected);
+	assert_eq!(expected, sp_io::hashing::blake2_256(&canonical_json(EXAMPLE.as_bytes()).unwrap()));
+
+	let changed = EXAMPLE.replace("\"job_total_tasks\": 750", "\"job_total_tasks\": 751");
+	assert_ne!(hash(changed.as_bytes()).unwrap(), expected);
+}
@@ -1,3 +1,4 @@
+use std::str::FromStr;
 use structopt::StructOpt;
 use sc_cli::RunCmd;
 
@@ -8,6 +9,33 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks without Aura and Grandpa for development and testing:
+	/// `instant` seals a block for every transaction, `manual` seals a block
+	/// on every `engine_createBlock` RPC call.
+	#[structopt(long, possible_values = &["instant", "manual"])]
+	pub sealing: Option<Sealing>,
+}
+
+/// How blocks are sealed by a development node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal a block when requested via the `engine_createBlock` RPC.
+	Manual,
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"instant" => Ok(Sealing::Instant),
+			"manual" => Ok(Sealing::Manual),
+			_ => Err(format!("Unknown sealing `{}`, expected `instant` or `manual`", s)),
+		}
+	}
 }
 
 #[derive(Debug, StructOpt)]
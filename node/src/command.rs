@@ -126,8 +126,10 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| match config.role {
-				Role::Light => service::new_light(config),
+			let sealing = cli.sealing;
+			runner.run_node_until_exit(|config| match (config.role, sealing) {
+				(Role::Light, _) => service::new_light(config),
+				(_, Some(sealing)) => service::new_manual_seal(config, sealing),
 				_ => service::new_full(config),
 			})
 		}
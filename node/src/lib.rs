@@ -1,3 +1,5 @@
 pub mod chain_spec;
 pub mod service;
 pub mod rpc;
+pub mod cli;
+pub mod command;
@@ -0,0 +1,388 @@
+//! Subscriptions to the state changes of escrows.
+//!
+//! The escrow events of every new best or finalized block are decoded in the node
+//! and sent to the subscribers whose filter matches the escrow.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use codec::Decode;
+use frame_support::storage::StorageMap;
+use futures::{future, StreamExt, TryStreamExt};
+use jsonrpc_core::{futures::{Future, Sink}, Result as RpcResult};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use log::warn;
+use node_template_runtime::{
+	opaque::Block, pallet_escrow::{self, EscrowId, EscrowInfo, EscrowStatus, FactoryId},
+	AccountId, Balance, Event, Hash, Moment, Runtime,
+};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::{generic::BlockId, traits::Header as _};
+
+type EscrowEvent = pallet_escrow::Event<Runtime>;
+
+/// The escrows a subscription is interested in.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EscrowFilter {
+	/// The escrow with the given id.
+	Id(EscrowId),
+	/// All escrows of the factory.
+	FactoryId(FactoryId),
+	/// All escrows the account is the escrow account, canceller or an oracle of.
+	Account(AccountId),
+}
+
+/// The blocks a subscription is notified about.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockSelection {
+	/// Every new best block. Changes may be reverted by reorgs.
+	Best,
+	/// Every finalized block.
+	Finalized,
+}
+
+impl Default for BlockSelection {
+	fn default() -> Self {
+		BlockSelection::Best
+	}
+}
+
+/// A change of an escrow in a block.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EscrowUpdate {
+	/// The block containing the change.
+	pub block: Hash,
+	/// The changed escrow.
+	pub escrow_id: EscrowId,
+	/// The event describing the change.
+	pub event: EscrowEvent,
+	/// The status of the escrow after the block, `None` if the escrow was aborted.
+	pub status: Option<EscrowStatus>,
+}
+
+/// Escrow RPC methods.
+#[rpc(server)]
+pub trait EscrowSubscriptionApi {
+	/// RPC metadata
+	type Metadata;
+
+	/// Subscribe to the changes of the escrows matching `filter` at best or finalized blocks.
+	#[pubsub(subscription = "escrow_escrow", subscribe, name = "escrow_subscribeEscrow")]
+	fn subscribe_escrow(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<EscrowUpdate>,
+		filter: EscrowFilter,
+		at: Option<BlockSelection>,
+	);
+
+	/// Unsubscribe from escrow changes.
+	#[pubsub(subscription = "escrow_escrow", unsubscribe, name = "escrow_unsubscribeEscrow")]
+	fn unsubscribe_escrow(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+}
+
+/// Implements the escrow subscriptions by reading the events and escrows from storage.
+pub struct EscrowSubscriptions<C, BE> {
+	client: Arc<C>,
+	manager: SubscriptionManager,
+	_marker: std::marker::PhantomData<BE>,
+}
+
+impl<C, BE> EscrowSubscriptions<C, BE> {
+	/// Create subscriptions reading from `client`, managed by `manager`.
+	pub fn new(client: Arc<C>, manager: SubscriptionManager) -> Self {
+		Self { client, manager, _marker: Default::default() }
+	}
+}
+
+impl<C, BE> EscrowSubscriptionApi for EscrowSubscriptions<C, BE> where
+	BE: Backend<Block> + 'static,
+	C: StorageProvider<Block, BE> + BlockchainEvents<Block>,
+	C: Send + Sync + 'static,
+{
+	type Metadata = sc_rpc::Metadata;
+
+	fn subscribe_escrow(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<EscrowUpdate>,
+		filter: EscrowFilter,
+		at: Option<BlockSelection>,
+	) {
+		let client = self.client.clone();
+		let blocks = match at.unwrap_or_default() {
+			BlockSelection::Best => self.client.import_notification_stream()
+				.filter(|notification| future::ready(notification.is_new_best))
+				.map(|notification| notification.header)
+				.boxed(),
+			BlockSelection::Finalized => self.client.finality_notification_stream()
+				.map(|notification| notification.header)
+				.boxed(),
+		};
+		let updates = blocks
+			.flat_map(move |header| {
+				let updates = escrow_updates(&*client, header.hash(), *header.parent_hash(), &filter);
+				futures::stream::iter(updates)
+			})
+			.map(|update| Ok::<_, ()>(Ok(update)))
+			.compat();
+
+		self.manager.add(subscriber, |sink| {
+			sink
+				.sink_map_err(|e| warn!("Error sending escrow notifications: {:?}", e))
+				.send_all(updates)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_escrow(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.manager.cancel(id))
+	}
+}
+
+/// The escrow events of the block `hash` that match `filter`.
+fn escrow_updates<C, BE>(client: &C, hash: Hash, parent: Hash, filter: &EscrowFilter) -> Vec<EscrowUpdate> where
+	BE: Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	let events = match storage::<_, _, Vec<frame_system::EventRecord<Event, Hash>>>(client, hash, events_key()) {
+		Some(events) => events,
+		None => return vec![],
+	};
+	matching_updates(hash, parent, events, filter, |at, id| escrow(client, at, id))
+}
+
+/// The updates for the escrow `events` of the block `hash` that match `filter`.
+///
+/// `escrow` looks up an escrow at a block. Escrows created and aborted in the block are in
+/// neither state, they are matched by the accounts of their `Pending` event. Their factory is
+/// unknown, so their updates are sent to all factory subscriptions.
+fn matching_updates(
+	hash: Hash,
+	parent: Hash,
+	events: Vec<frame_system::EventRecord<Event, Hash>>,
+	filter: &EscrowFilter,
+	escrow: impl Fn(Hash, EscrowId) -> Option<EscrowInfo<Moment, AccountId, Balance>>,
+) -> Vec<EscrowUpdate> {
+	let events: Vec<(EscrowId, EscrowEvent)> = events.into_iter()
+		.filter_map(|record| match record.event {
+			Event::pallet_escrow(event) => event.escrow_id().map(|id| (id, event)),
+			_ => None,
+		})
+		.collect();
+	// The accounts of the escrows created in the block.
+	let created: BTreeMap<EscrowId, Vec<AccountId>> = events.iter()
+		.filter_map(|(id, event)| match event {
+			pallet_escrow::RawEvent::Pending(_, creator, _, _, account, oracles) => {
+				let accounts = vec![creator.clone(), account.clone()].into_iter()
+					.chain(oracles.iter().map(|oracle| oracle.account.clone()));
+				Some((*id, accounts.collect()))
+			}
+			_ => None,
+		})
+		.collect();
+	events.into_iter()
+		.filter_map(|(id, event)| {
+			let current = escrow(hash, id);
+			let matches = match filter {
+				EscrowFilter::Id(wanted) => *wanted == id,
+				_ => {
+					// Aborted escrows are only found at the parent block.
+					let escrow = current.as_ref().cloned().or_else(|| escrow(parent, id));
+					match (filter, escrow, created.get(&id)) {
+						(EscrowFilter::FactoryId(factory), Some(escrow), _) => escrow.factory() == *factory,
+						(EscrowFilter::Account(who), Some(escrow), _) => escrow.involves(who),
+						(EscrowFilter::FactoryId(_), None, Some(_)) => true,
+						(EscrowFilter::Account(who), None, Some(accounts)) => accounts.contains(who),
+						_ => false,
+					}
+				}
+			};
+			if matches {
+				Some(EscrowUpdate {
+					block: hash,
+					escrow_id: id,
+					event,
+					status: current.map(|escrow| escrow.status()),
+				})
+			} else {
+				None
+			}
+		})
+		.collect()
+}
+
+fn escrow<C, BE>(client: &C, at: Hash, id: EscrowId) -> Option<EscrowInfo<Moment, AccountId, Balance>> where
+	BE: Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	storage(client, at, escrow_key(id))
+}
+
+fn storage<C, BE, T: Decode>(client: &C, at: Hash, key: StorageKey) -> Option<T> where
+	BE: Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	let data = client.storage(&BlockId::Hash(at), &key)
+		.map_err(|e| warn!("Failed to read escrow storage at {}: {:?}", at, e))
+		.ok()??;
+	T::decode(&mut &data.0[..])
+		.map_err(|e| warn!("Failed to decode escrow storage at {}: {:?}", at, e))
+		.ok()
+}
+
+/// The key of `System::Events`, whose storage type is private to `frame_system`.
+fn events_key() -> StorageKey {
+	StorageKey([twox_128(b"System"), twox_128(b"Events")].concat())
+}
+
+fn escrow_key(id: EscrowId) -> StorageKey {
+	StorageKey(pallet_escrow::Escrows::<Runtime>::hashed_key_for(id))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{assert_ok, traits::UnfilteredDispatchable};
+	use node_template_runtime::{
+		pallet_escrow::{FixedFeeTiming, ManifestHashAlgorithm, Oracle, OracleRole},
+		pallet_kvstore::{self, PUBLIC_KEY},
+		pallet_parameters, Origin,
+	};
+	use sp_runtime::Percent;
+	use std::collections::BTreeMap;
+
+	type Escrow = pallet_escrow::Module<Runtime>;
+	type KVStore = pallet_kvstore::Module<Runtime>;
+	type System = frame_system::Module<Runtime>;
+
+	const LAUNCHER: [u8; 32] = [1; 32];
+	const REPUTATION_ORACLE: [u8; 32] = [2; 32];
+	const RECORDING_ORACLE: [u8; 32] = [3; 32];
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_parameters::GenesisConfig::<Runtime> {
+			standard_duration: 1000,
+			string_limit: 100,
+			bulk_accounts_limit: 10,
+			handlers_limit: 20,
+			escrows_per_factory_limit: 20,
+			manifest_gateways: vec![],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Create an escrow in `factory`, whose oracles have published their keys.
+	fn create_escrow(factory: FactoryId) {
+		let oracles = vec![(REPUTATION_ORACLE, OracleRole::Reputation), (RECORDING_ORACLE, OracleRole::Recording)]
+			.into_iter()
+			.map(|(account, role)| {
+				let account = AccountId::from(account);
+				assert_ok!(KVStore::set(Origin::signed(account.clone()), PUBLIC_KEY.to_vec(), vec![7; 32]));
+				Oracle {
+					account,
+					role,
+					fee: Percent::from_percent(10),
+					fixed_fee: 0,
+					fixed_fee_timing: FixedFeeTiming::Completion,
+				}
+			})
+			.collect();
+		assert_ok!(Escrow::create(
+			Origin::signed(LAUNCHER.into()),
+			b"manifest.url".to_vec(),
+			b"0xdev".to_vec(),
+			ManifestHashAlgorithm::Other,
+			factory,
+			oracles
+		));
+	}
+
+	/// Abort escrow `id` as its launcher.
+	fn abort(id: EscrowId) {
+		let call = pallet_escrow::Call::<Runtime>::abort(id);
+		assert_ok!(call.dispatch_bypass_filter(Origin::signed(LAUNCHER.into())));
+	}
+
+	/// Read an escrow from the externalities by the key the subscriptions use.
+	fn read_escrow(id: EscrowId) -> Option<EscrowInfo<Moment, AccountId, Balance>> {
+		sp_io::storage::get(&escrow_key(id).0).map(|data| Decode::decode(&mut &data[..]).unwrap())
+	}
+
+	#[test]
+	fn storage_keys_match_runtime() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Escrow::create_factory(Origin::signed(LAUNCHER.into())));
+			create_escrow(0);
+			assert_eq!(read_escrow(0), Escrow::escrow(0));
+			assert!(read_escrow(0).is_some());
+			let events: Vec<frame_system::EventRecord<Event, Hash>> =
+				Decode::decode(&mut &sp_io::storage::get(&events_key().0).unwrap()[..]).unwrap();
+			assert_eq!(events, System::events());
+		});
+	}
+
+	#[test]
+	fn updates_are_filtered_by_escrow() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Escrow::create_factory(Origin::signed(LAUNCHER.into())));
+			assert_ok!(Escrow::create_factory(Origin::signed(LAUNCHER.into())));
+			create_escrow(0);
+			create_escrow(1);
+			create_escrow(1);
+			// The escrows as of the parent block, before escrow 2 is aborted.
+			let parent_escrows: BTreeMap<_, _> = (0..3).map(|id| (id, read_escrow(id))).collect();
+			abort(2);
+			// Escrow 3 is created and aborted in the block, it is in neither state.
+			create_escrow(0);
+			abort(3);
+
+			let (block, parent) = (Hash::repeat_byte(2), Hash::repeat_byte(1));
+			let events = System::events();
+			let updates = |filter: EscrowFilter| {
+				matching_updates(block, parent, events.clone(), &filter, |at, id| {
+					if at == block { read_escrow(id) } else { parent_escrows.get(&id).cloned().flatten() }
+				})
+				.into_iter()
+				.map(|update| (update.escrow_id, update.status))
+				.collect::<Vec<_>>()
+			};
+
+			assert_eq!(updates(EscrowFilter::Id(1)), vec![(1, Some(EscrowStatus::Pending))]);
+			assert_eq!(updates(EscrowFilter::Id(4)), vec![]);
+			assert_eq!(updates(EscrowFilter::Id(3)), vec![(3, None), (3, None)]);
+			// The factory of escrow 3 is unknown, its updates are sent to all factory subscriptions.
+			assert_eq!(
+				updates(EscrowFilter::FactoryId(0)),
+				vec![(0, Some(EscrowStatus::Pending)), (3, None), (3, None)]
+			);
+			// The creation and abort events of escrow 2, found at the parent after the abort.
+			assert_eq!(
+				updates(EscrowFilter::FactoryId(1)),
+				vec![(1, Some(EscrowStatus::Pending)), (2, None), (2, None), (3, None), (3, None)]
+			);
+			// Escrow 3 is matched by the accounts of its creation event.
+			assert_eq!(updates(EscrowFilter::Account(RECORDING_ORACLE.into())).len(), 6);
+			assert_eq!(updates(EscrowFilter::Account(LAUNCHER.into())).len(), 6);
+			assert_eq!(updates(EscrowFilter::Account(AccountId::from([4; 32]))), vec![]);
+		});
+	}
+}
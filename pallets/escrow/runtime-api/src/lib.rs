@@ -0,0 +1,37 @@
+//! Runtime API definition for the escrow pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_escrow::{EscrowId, EscrowParty, IntermediateResult, Oracle, PayoutPreview};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// Queries into the escrow state that are not convenient to do via raw storage access.
+	pub trait EscrowApi<AccountId, BlockNumber, Balance> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Balance: Codec,
+	{
+		/// Get the intermediate results noted for the escrow at `id`, oldest first.
+		fn intermediate_results(id: EscrowId) -> Vec<IntermediateResult<BlockNumber, AccountId>>;
+
+		/// Get the oracles and their fee shares for the escrow at `id`.
+		fn oracles(id: EscrowId) -> Vec<Oracle<AccountId, Balance>>;
+
+		/// Get the public keys of the oracles recorded when the escrow at `id` was created.
+		///
+		/// Results of the escrow have to be encrypted to these keys.
+		fn oracle_keys(id: EscrowId) -> Vec<(AccountId, Vec<u8>)>;
+
+		/// Preview the oracle and protocol fees of paying out `amounts` from the escrow at `id`.
+		///
+		/// Returns `None` if the escrow does not exist.
+		fn preview_payout(id: EscrowId, amounts: Vec<Balance>) -> Option<PayoutPreview<AccountId, Balance>>;
+
+		/// Get a page of the ids of the escrows `who` is a party of as `party`, in ascending order.
+		///
+		/// Returns at most `limit` ids greater than `start_after`.
+		fn escrows_of(who: AccountId, party: EscrowParty, start_after: Option<EscrowId>, limit: u32) -> Vec<EscrowId>;
+	}
+}
@@ -30,6 +30,32 @@ fn set_status<T: Trait>(id: EscrowId, status: EscrowStatus) -> DispatchResult {
 	})
 }
 
+/// Sets up the maximum number of oracles with fees summing up to at most 50%.
+fn oracles<T: Trait>() -> Vec<Oracle<T::AccountId, BalanceOf<T>>> {
+	oracles_with_fixed_fees::<T>(Zero::zero(), FixedFeeTiming::Completion)
+}
+
+/// Sets up the maximum number of oracles, each with the given fixed fee in addition to the percentage.
+///
+/// Every oracle publishes a public key.
+fn oracles_with_fixed_fees<T: Trait>(fixed_fee: BalanceOf<T>, fixed_fee_timing: FixedFeeTiming) -> Vec<Oracle<T::AccountId, BalanceOf<T>>> {
+	let count = T::OraclesLimit::get();
+	let fee = Percent::from_percent((50 / count) as u8);
+	(0..count)
+		.map(|i| {
+			let role = match i {
+				0 => OracleRole::Reputation,
+				1 => OracleRole::Recording,
+				2 => OracleRole::Exchange,
+				_ => OracleRole::Other,
+			};
+			let account: T::AccountId = account("oracle", i, SEED);
+			T::KeyProvider::set_public_key(&account, vec![i as u8; 32]);
+			Oracle { account, role, fee, fixed_fee, fixed_fee_timing }
+		})
+		.collect()
+}
+
 benchmarks! {
 	_ { }
 
@@ -48,27 +74,42 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let oracles = oracles::<T>();
 		let factory_id = 0;
 
-	} : _(RawOrigin::Signed(caller.clone()), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake)
+	} : _(RawOrigin::Signed(caller.clone()), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone())
 	verify {
 		let id = 0;
 		let escrow = Escrows::<T>::get(id).unwrap();
 		assert_eq!(escrow.status, EscrowStatus::Pending);
-		let all_handlers = vec![caller.clone(), reputation_oracle, recording_oracle];
+		let all_handlers = [vec![caller.clone()], oracles.iter().map(|o| o.account.clone()).collect()].concat();
 		for handler in all_handlers {
 			assert!(Escrow::<T>::is_trusted_handler(id, handler));
 		}
-		assert_last_event::<T>(RawEvent::Pending(id, caller, manifest_url, manifest_hash, Escrow::<T>::account_id_for(id)).into())
+		assert_last_event::<T>(RawEvent::Pending(id, caller, manifest_url, manifest_hash, Escrow::<T>::account_id_for(id), oracles).into())
+	}
+
+	create_from_terms {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let factory_id = 0;
+		let terms = JobTerms { total_tasks: 750, price_per_task: 64u32.into(), min_repeats: 4, max_repeats: 5 };
+
+	} : _(RawOrigin::Signed(caller.clone()), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone(), terms.clone())
+	verify {
+		let id = 0;
+		assert_eq!(Escrows::<T>::get(id).unwrap().terms, Some(terms));
+		assert_last_event::<T>(RawEvent::Pending(id, caller, manifest_url, manifest_hash, Escrow::<T>::account_id_for(id), oracles).into())
 	}
 
 	add_trusted_handlers {
-		// By default `create` sets 3 trusted handlers (sender, rep_oracle, rec_oracle)
-		let h in 1..((T::HandlersLimit::get() - 3) as u32);
+		// By default `create` sets the sender and every oracle as trusted handlers
+		let h in 1..((T::HandlersLimit::get() - T::OraclesLimit::get() - 1) as u32);
 
 		let caller: T::AccountId = whitelisted_caller();
 		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
@@ -77,13 +118,10 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let oracles = oracles::<T>();
 		let factory_id = 0;
 
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
 	} : _(RawOrigin::Signed(caller.clone()), id, handlers.clone())
 	verify {
@@ -93,8 +131,8 @@ benchmarks! {
 	}
 
 	abort {
-		// By default `create` sets 3 trusted handlers (sender, rep_oracle, rec_oracle)
-		let h in 1..((T::HandlersLimit::get() - 3) as u32);
+		// By default `create` sets the sender and every oracle as trusted handlers
+		let h in 1..((T::HandlersLimit::get() - T::OraclesLimit::get() - 1) as u32);
 		let f in 1..((T::EscrowsPerFactoryLimit::get() - 1) as u32);
 		let caller: T::AccountId = whitelisted_caller();
 		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
@@ -103,16 +141,13 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let oracles = oracles::<T>();
 		let factory_id = 0;
 		
 		for x in 0..f {
-			Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake)?;
+			Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone())?;
 		}
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
 		assert_eq!(Escrow::<T>::add_trusted_handlers(RawOrigin::Signed(caller.clone()).into(), id, handlers.clone()), Ok(()));
 		let escrow = Escrows::<T>::get(id).unwrap();
@@ -122,7 +157,7 @@ benchmarks! {
 	} : _(RawOrigin::Signed(caller.clone()), id)
 	verify {
 		assert_eq!(Escrows::<T>::get(id), None);
-		let all_handlers = [handlers, vec![caller.clone(), reputation_oracle, recording_oracle]].concat();
+		let all_handlers = [handlers, vec![caller.clone()], oracles.into_iter().map(|o| o.account).collect()].concat();
 		for handler in all_handlers {
 			assert!(!Escrow::<T>::is_trusted_handler(id, handler));
 		}
@@ -137,13 +172,10 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let oracles = oracles::<T>();
 		let factory_id = 0;
 
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
 		let escrow = Escrows::<T>::get(id).unwrap();
 		let amount = 1000u32;
@@ -162,18 +194,46 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let fixed_fee: BalanceOf<T> = 100_000u32.into();
+		let oracles = oracles_with_fixed_fees::<T>(fixed_fee, FixedFeeTiming::Completion);
 		let factory_id = 0;
 
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
+		let escrow = Escrows::<T>::get(id).unwrap();
+		T::Currency::make_free_balance_be(&escrow.account, fixed_fee * T::OraclesLimit::get().into());
+		Escrow::<T>::store_final_results(RawOrigin::Signed(caller.clone()).into(), id, manifest_url.clone(), manifest_hash.clone())?;
+		Escrow::<T>::accept_results(RawOrigin::Signed(caller.clone()).into(), id)?;
 		set_status::<T>(id, EscrowStatus::Paid)?;
 	} : _(RawOrigin::Signed(caller.clone()), id)
 	verify {
 		assert_eq!(Escrows::<T>::get(id).unwrap().status, EscrowStatus::Complete);
+		for oracle in oracles {
+			assert_eq!(T::Currency::free_balance(&oracle.account), fixed_fee);
+		}
+	}
+
+	fund {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let fixed_fee: BalanceOf<T> = 100_000u32.into();
+		let oracles = oracles_with_fixed_fees::<T>(fixed_fee, FixedFeeTiming::Upfront);
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		let amount = fixed_fee * T::OraclesLimit::get().into() * 2u32.into();
+		T::Currency::make_free_balance_be(&caller, amount * 2u32.into());
+	} : _(RawOrigin::Signed(caller.clone()), id, amount)
+	verify {
+		assert!(Escrows::<T>::get(id).unwrap().upfront_fees_paid);
+		for oracle in oracles {
+			assert_eq!(T::Currency::free_balance(&oracle.account), fixed_fee);
+		}
 	}
 
 	note_intermediate_results {
@@ -183,18 +243,20 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let oracles = oracles::<T>();
 		let factory_id = 0;
 
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
 		let url = vec![junk; T::StringLimit::get()];
 		let hash = vec![junk; T::StringLimit::get()];
+		// Fill the history so the oldest entry has to be dropped.
+		for _ in 0..T::IntermediateResultsLimit::get() {
+			Escrow::<T>::note_intermediate_results(RawOrigin::Signed(caller.clone()).into(), id, url.clone(), hash.clone())?;
+		}
 	} : _(RawOrigin::Signed(caller.clone()), id, url.clone(), hash.clone())
 	verify {
+		assert_eq!(IntermediateResults::<T>::get(id).len() as u32, T::IntermediateResultsLimit::get());
 		assert_last_event::<T>(RawEvent::IntermediateResults(id, url, hash).into())
 	}
 
@@ -205,13 +267,10 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let oracles = oracles::<T>();
 		let factory_id = 0;
 
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
 		let url = vec![junk; T::StringLimit::get()];
 		let hash = vec![junk; T::StringLimit::get()];
@@ -220,6 +279,141 @@ benchmarks! {
 		assert_eq!(FinalResults::get(id), Some(ResultInfo { results_url: url, results_hash: hash}));
 	}
 
+	store_signed_final_results {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let mut oracles = oracles::<T>();
+		let recording_oracle = T::BenchmarkSigner::account();
+		T::KeyProvider::set_public_key(&recording_oracle, vec![junk; 32]);
+		oracles[1].account = recording_oracle.clone();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		// Replace stored results, which also has to check them.
+		Escrow::<T>::store_final_results(RawOrigin::Signed(caller.clone()).into(), id, manifest_url.clone(), manifest_hash.clone())?;
+		let url = vec![junk; T::StringLimit::get()];
+		let hash = vec![junk + 1; T::StringLimit::get()];
+		let signature = T::BenchmarkSigner::sign(&recording_oracle, &(id, ResultsNonce::get(id), &url, &hash).encode());
+	} : _(RawOrigin::Signed(caller.clone()), id, url.clone(), hash.clone(), signature)
+	verify {
+		assert_eq!(FinalResults::get(id), Some(ResultInfo { results_url: url, results_hash: hash}));
+		assert_eq!(ResultsNonce::get(id), 2);
+	}
+
+	commit_results {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let recording_oracle = oracles[1].account.clone();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		let commitment = Escrow::<T>::results_commitment_for(id, &recording_oracle, &manifest_url, &manifest_hash, &manifest_url);
+	} : _(RawOrigin::Signed(recording_oracle.clone()), id, commitment)
+	verify {
+		assert!(ResultsCommitments::<T>::contains_key(id, &recording_oracle));
+		assert_last_event::<T>(RawEvent::ResultsCommitted(id, recording_oracle).into())
+	}
+
+	reveal_results {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let recording_oracle = oracles[1].account.clone();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		let url = vec![junk; T::StringLimit::get()];
+		let hash = vec![junk; T::StringLimit::get()];
+		let salt = vec![junk; T::StringLimit::get()];
+		let commitment = Escrow::<T>::results_commitment_for(id, &recording_oracle, &url, &hash, &salt);
+		Escrow::<T>::commit_results(RawOrigin::Signed(recording_oracle.clone()).into(), id, commitment)?;
+	} : _(RawOrigin::Signed(recording_oracle.clone()), id, url.clone(), hash.clone(), salt)
+	verify {
+		assert_eq!(FinalResults::get(id), Some(ResultInfo { results_url: url, results_hash: hash}));
+		assert_last_event::<T>(RawEvent::ResultsRevealed(id, recording_oracle).into())
+	}
+
+	flag_unrevealed {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let recording_oracle = oracles[1].account.clone();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		let commitment = Escrow::<T>::results_commitment_for(id, &recording_oracle, &manifest_url, &manifest_hash, &manifest_url);
+		Escrow::<T>::commit_results(RawOrigin::Signed(recording_oracle.clone()).into(), id, commitment)?;
+		let after_window = <timestamp::Module<T>>::get() + T::RevealWindow::get() + 1u32.into();
+		<timestamp::Module<T>>::set_timestamp(after_window);
+	} : _(RawOrigin::Signed(caller.clone()), id, recording_oracle.clone())
+	verify {
+		assert_eq!(MissedReveals::<T>::get(&recording_oracle), 1);
+		assert_last_event::<T>(RawEvent::CommitmentUnrevealed(id, recording_oracle).into())
+	}
+
+	accept_results {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		let url = vec![junk; T::StringLimit::get()];
+		let hash = vec![junk; T::StringLimit::get()];
+		Escrow::<T>::store_final_results(RawOrigin::Signed(caller.clone()).into(), id, url, hash)?;
+	} : _(RawOrigin::Signed(caller.clone()), id)
+	verify {
+		assert_eq!(ResultsReview::<T>::get(id), Some(ResultsAcceptance::Accepted));
+		assert_last_event::<T>(RawEvent::ResultsAccepted(id).into())
+	}
+
+	reject_results {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		let url = vec![junk; T::StringLimit::get()];
+		let hash = vec![junk; T::StringLimit::get()];
+		Escrow::<T>::store_final_results(RawOrigin::Signed(caller.clone()).into(), id, url, hash)?;
+	} : _(RawOrigin::Signed(caller.clone()), id)
+	verify {
+		assert_eq!(ResultsReview::<T>::get(id), Some(ResultsAcceptance::Rejected));
+		assert_last_event::<T>(RawEvent::ResultsRejected(id).into())
+	}
+
 	bulk_payout {
 		let b in 1..(T::BulkAccountsLimit::get() as u32);
 
@@ -229,31 +423,72 @@ benchmarks! {
 		let junk = 42;
 		let manifest_url = vec![junk; T::StringLimit::get()];
 		let manifest_hash = vec![junk; T::StringLimit::get()];
-		let reputation_oracle: T::AccountId = account("oracle", 0, SEED);
-		let recording_oracle: T::AccountId = account("oracle", 1, SEED);
-		let reputation_oracle_stake = Percent::from_percent(10);
-		let recording_oracle_stake = Percent::from_percent(10);
+		let fixed_fee: BalanceOf<T> = 100_000u32.into();
+		let oracles = oracles_with_fixed_fees::<T>(fixed_fee, FixedFeeTiming::Upfront);
 		let factory_id = 0;
 
-		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), factory_id, reputation_oracle.clone(), recording_oracle.clone(), reputation_oracle_stake, recording_oracle_stake), Ok(()));
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
 		let id = 0;
 		let escrow = Escrows::<T>::get(id).unwrap();
 		// Need a high enough value so we don't run into ExistentialDeposit issues for the oracles.
 		let amount: BalanceOf<T> = 100_000u32.into();
 		let total_amount = amount * b.into();
-		T::Currency::make_free_balance_be(&escrow.account, total_amount.into());
+		// The upfront fees are paid as part of the payout.
+		let upfront_fees = fixed_fee * T::OraclesLimit::get().into();
+		T::Currency::make_free_balance_be(&escrow.account, total_amount + upfront_fees);
 		let recipients: Vec<T::AccountId> = (0..b).map(|b| account("recipient", b, SEED)).collect();
 		let amounts = vec![amount; b as usize];
+		Escrow::<T>::store_final_results(RawOrigin::Signed(caller.clone()).into(), id, manifest_url.clone(), manifest_hash.clone())?;
+		Escrow::<T>::accept_results(RawOrigin::Signed(caller.clone()).into(), id)?;
+		let protocol_fee = Percent::from_percent(10);
+		ProtocolFee::put(protocol_fee);
 	} : _(RawOrigin::Signed(caller.clone()), id, recipients.clone(), amounts.clone())
 	verify {
-		assert_eq!(T::Currency::free_balance(&reputation_oracle), reputation_oracle_stake.mul_floor(total_amount));
-		assert_eq!(T::Currency::free_balance(&recording_oracle), recording_oracle_stake.mul_floor(total_amount));
-		let received =  amount - reputation_oracle_stake.mul_floor(amount) - recording_oracle_stake.mul_floor(amount);
+		let mut received = amount;
+		let mut paid_fees = Vec::new();
+		for oracle in oracles {
+			let fee = oracle.fee.mul_floor(amount) * b.into();
+			assert_eq!(T::Currency::free_balance(&oracle.account), fee + fixed_fee);
+			received -= oracle.fee.mul_floor(amount);
+			paid_fees.push((oracle.account, fee));
+		}
+		let protocol_fee_per_amount = protocol_fee.mul_floor(amount).min(received);
+		received -= protocol_fee_per_amount;
 		for r in recipients {
 			assert_eq!(T::Currency::free_balance(&r), received);
 		}
 		assert_eq!(Escrows::<T>::get(id).unwrap().status, EscrowStatus::Paid);
-		assert_last_event::<T>(RawEvent::BulkPayout(id).into());
+		assert_last_event::<T>(RawEvent::BulkPayout(id, paid_fees, protocol_fee_per_amount * b.into()).into());
+	}
+
+	set_protocol_fee {
+		let origin = T::ProtocolFeeOrigin::successful_origin();
+		let fee = Percent::from_percent(5);
+	} : { Escrow::<T>::set_protocol_fee(origin, fee)? }
+	verify {
+		assert_eq!(ProtocolFee::get(), fee);
+		assert_last_event::<T>(RawEvent::ProtocolFeeSet(fee).into())
+	}
+
+	report_manifest_check {
+		let caller: T::AccountId = whitelisted_caller();
+		<Escrow<T>>::create_factory(RawOrigin::Signed(caller.clone()).into()).unwrap();
+
+		let junk = 42;
+		let manifest_url = vec![junk; T::StringLimit::get()];
+		let manifest_hash = vec![junk; T::StringLimit::get()];
+		let oracles = oracles::<T>();
+		let factory_id = 0;
+
+		assert_eq!(Escrow::<T>::create(RawOrigin::Signed(caller.clone()).into(), manifest_url.clone(), manifest_hash.clone(), ManifestHashAlgorithm::Other, factory_id, oracles.clone()), Ok(()));
+		let id = 0;
+		// The check is only queued if the runtime has gateways configured.
+		PendingManifestChecks::insert(id, true);
+		T::ManifestCheckers::add(&caller);
+	} : _(RawOrigin::Signed(caller), id, false)
+	verify {
+		assert_eq!(ManifestChecks::get(id), Some(false));
+		assert_last_event::<T>(RawEvent::ManifestChecked(id, false).into())
 	}
 
 }
@@ -278,6 +513,13 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn escrow_create_from_terms() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create_from_terms::<Test>());
+		});
+	}
+
 	#[test]
 	fn escrow_add_trusted_handlers() {
 		new_test_ext().execute_with(|| {
@@ -306,6 +548,13 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn escrow_fund() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_fund::<Test>());
+		});
+	}
+
 	#[test]
 	fn escrow_note_intermediate_results() {
 		new_test_ext().execute_with(|| {
@@ -320,10 +569,66 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn escrow_store_signed_final_results() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_store_signed_final_results::<Test>());
+		});
+	}
+
+	#[test]
+	fn escrow_commit_results() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_commit_results::<Test>());
+		});
+	}
+
+	#[test]
+	fn escrow_reveal_results() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_reveal_results::<Test>());
+		});
+	}
+
+	#[test]
+	fn escrow_flag_unrevealed() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_flag_unrevealed::<Test>());
+		});
+	}
+
+	#[test]
+	fn escrow_accept_results() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_accept_results::<Test>());
+		});
+	}
+
+	#[test]
+	fn escrow_reject_results() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_reject_results::<Test>());
+		});
+	}
+
 	#[test]
 	fn escrow_bulk_payout() {
 		new_test_ext().execute_with(|| {
 			assert_ok!(test_benchmark_bulk_payout::<Test>());
 		});
 	}
+
+	#[test]
+	fn escrow_set_protocol_fee() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_protocol_fee::<Test>());
+		});
+	}
+
+	#[test]
+	fn escrow_report_manifest_check() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_report_manifest_check::<Test>());
+		});
+	}
 }
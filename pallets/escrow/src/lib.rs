@@ -2,19 +2,28 @@
 
 use codec::{Decode, Encode};
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage,
-    dispatch::{DispatchError, DispatchResult},
+    debug, decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchError, DispatchResult, Parameter},
     ensure,
     storage::{with_transaction, TransactionOutcome},
-    traits::{Currency, ExistenceRequirement::AllowDeath, Get},
+    traits::{
+        Contains, Currency, EnsureOrigin, ExistenceRequirement::AllowDeath, Get, OnUnbalanced, WithdrawReason,
+    },
     weights::Weight,
 };
-use frame_system::ensure_signed;
+use frame_system::{
+    ensure_signed,
+    offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
+};
+use pallet_kvstore::KeyProvider;
 use sp_runtime::{
-    traits::{AccountIdConversion, Saturating, Zero},
-    ModuleId, Percent,
+    offchain::{http, storage::StorageValueRef, Duration},
+    traits::{AccountIdConversion, AtLeast32BitUnsigned, Hash, IdentifyAccount, Saturating, Verify, Zero},
+    KeyTypeId, ModuleId, Percent, RuntimeAppPublic,
 };
 use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 mod mock;
@@ -24,6 +33,8 @@ mod tests;
 
 mod benchmarks;
 
+pub mod migrations;
+
 use pallet_timestamp as timestamp;
 
 /// Id used for storing all information related to an escrow.
@@ -34,9 +45,54 @@ pub type FactoryId = u128;
 
 const MODULE_ID: ModuleId = ModuleId(*b"escrowhp");
 
+/// Prefix of the offchain storage keys remembering when a manifest check was submitted.
+const MANIFEST_CHECK_KEY: &[u8] = b"escrow::manifest-check::";
+
+/// Prefix of the offchain storage keys counting the failed attempts to fetch a manifest.
+const MANIFEST_FETCH_ATTEMPTS_KEY: &[u8] = b"escrow::manifest-fetch-attempts::";
+
+/// The number of times the gateway may answer without the manifest before the check fails.
+const MANIFEST_FETCH_ATTEMPTS: u32 = 10;
+
+/// The number of blocks after which a manifest check that did not make it into a block
+/// is submitted again.
+const MANIFEST_CHECK_RETRY: u32 = 5;
+
+/// How long the offchain worker waits for a manifest, in milliseconds.
+const MANIFEST_FETCH_TIMEOUT: u64 = 5_000;
+
+/// The maximum size of a manifest in bytes. Larger manifests fail the check.
+const MANIFEST_SIZE_LIMIT: usize = 1024 * 1024;
+
+/// The key type of the keys the offchain worker signs manifest check reports with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"hmtm");
+
+/// The sr25519 keys signing manifest check reports.
+///
+/// Validators insert the key of their account under `KEY_TYPE` to report manifest checks.
+pub mod crypto {
+    use super::KEY_TYPE;
+    use sp_core::sr25519::{Public as Sr25519Public, Signature as Sr25519Signature};
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        MultiSignature, MultiSigner,
+    };
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    /// Signs manifest check reports of runtimes with `MultiSignature` accounts.
+    pub struct ManifestCheckerId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for ManifestCheckerId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = Sr25519Signature;
+        type GenericPublic = Sr25519Public;
+    }
+}
+
 /// Configuration and state for an escrow.
 #[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
-pub struct EscrowInfo<Moment, AccountId> {
+pub struct EscrowInfo<Moment, AccountId, Balance> {
     /// Current status of the escrow. Is created as `Pending`.
     status: EscrowStatus,
     /// The expiry time of the escrow.
@@ -44,18 +100,192 @@ pub struct EscrowInfo<Moment, AccountId> {
     /// Location of the manifest specifying metadata for this escrow.
     manifest_url: Vec<u8>,
     manifest_hash: Vec<u8>,
-    reputation_oracle: AccountId,
-    recording_oracle: AccountId,
-    /// The amount payed to the reputation oracle at bulk payout.
-    reputation_oracle_stake: Percent,
-    /// The amount payed to the recording oracle at bulk payout.
-    recording_oracle_stake: Percent,
+    /// The algorithm `manifest_hash` was computed with.
+    manifest_hash_algorithm: ManifestHashAlgorithm,
+    /// The oracles receiving a fee at bulk payout. Their fees add up to at most 100%.
+    oracles: Vec<Oracle<AccountId, Balance>>,
+    /// Whether the upfront fixed fees of the oracles have been paid.
+    upfront_fees_paid: bool,
     /// The account that will be refunded to on cancel/abort.
     canceller: AccountId,
     /// The account id used to hold escrow funds.
     account: AccountId,
     /// The factory with which the escrow is associated.
-    factory: FactoryId
+    factory: FactoryId,
+    /// The public keys the oracles had published when the escrow was created.
+    ///
+    /// Results of the escrow have to be encrypted to these keys, even if the oracles rotate theirs.
+    oracle_keys: Vec<(AccountId, Vec<u8>)>,
+    /// The economic terms of the job if created with `create_from_terms`.
+    terms: Option<JobTerms<Balance>>,
+    /// The sum of all bulk payout amounts, including the fees taken from them.
+    paid_out: Balance,
+}
+
+impl<Moment, AccountId: PartialEq, Balance> EscrowInfo<Moment, AccountId, Balance> {
+    pub fn status(&self) -> EscrowStatus {
+        self.status
+    }
+
+    pub fn factory(&self) -> FactoryId {
+        self.factory
+    }
+
+    /// The account holding the funds of the escrow.
+    pub fn account(&self) -> &AccountId {
+        &self.account
+    }
+
+    pub fn oracle_keys(&self) -> &[(AccountId, Vec<u8>)] {
+        &self.oracle_keys
+    }
+
+    pub fn terms(&self) -> Option<&JobTerms<Balance>> {
+        self.terms.as_ref()
+    }
+
+    pub fn paid_out(&self) -> &Balance {
+        &self.paid_out
+    }
+
+    /// Whether `who` is the escrow account, the canceller or one of the oracles.
+    pub fn involves(&self, who: &AccountId) -> bool {
+        &self.account == who
+            || &self.canceller == who
+            || self.oracles.iter().any(|oracle| &oracle.account == who)
+    }
+}
+
+/// The economic terms of a job as given by its manifest.
+///
+/// Payouts of an escrow with terms never exceed the budget of `price_per_task` times
+/// `total_tasks`, and the escrow is funded with at most the budget plus the fixed oracle fees.
+/// The percentage oracle fees and the protocol fee are part of the budget, they are taken
+/// from the payouts.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct JobTerms<Balance> {
+    #[codec(compact)]
+    pub total_tasks: u32,
+    pub price_per_task: Balance,
+    /// The minimum number of answers per task.
+    #[codec(compact)]
+    pub min_repeats: u32,
+    /// The maximum number of answers per task.
+    #[codec(compact)]
+    pub max_repeats: u32,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> JobTerms<Balance> {
+    /// The total amount paid for all tasks, the task count times the price per task.
+    /// `None` on overflow.
+    pub fn budget(&self) -> Option<Balance> {
+        self.price_per_task.checked_mul(&self.total_tasks.into())
+    }
+
+    /// Whether the terms describe a job that can be paid out.
+    pub fn is_valid(&self) -> bool {
+        self.total_tasks > 0
+            && !self.price_per_task.is_zero()
+            && self.min_repeats > 0
+            && self.min_repeats <= self.max_repeats
+            && self.budget().is_some()
+    }
+}
+
+/// The algorithm the `manifest_hash` of an escrow was computed with.
+///
+/// `Canonical` hashes the canonical JSON form of `manifest::hash`, the other supported algorithms
+/// hash the manifest as published. Manifests hashed with `Other` are not checked.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub enum ManifestHashAlgorithm {
+    /// Blake2-256 of the canonical JSON form of the manifest.
+    Canonical,
+    /// SHA2-256.
+    Sha2_256,
+    /// Keccak-256.
+    Keccak256,
+    /// Blake2b-256.
+    Blake2_256,
+    /// An algorithm the offchain worker cannot check.
+    Other,
+}
+
+impl ManifestHashAlgorithm {
+    /// Whether the offchain worker can check manifests hashed with the algorithm.
+    pub fn is_supported(self) -> bool {
+        self != ManifestHashAlgorithm::Other
+    }
+
+    /// Hash the `document`, `None` for `Other` or if the canonical hash is requested for invalid JSON.
+    pub fn digest(self, document: &[u8]) -> Option<[u8; 32]> {
+        match self {
+            ManifestHashAlgorithm::Canonical => manifest::hash(document).ok(),
+            ManifestHashAlgorithm::Sha2_256 => Some(sp_io::hashing::sha2_256(document)),
+            ManifestHashAlgorithm::Keccak256 => Some(sp_io::hashing::keccak_256(document)),
+            ManifestHashAlgorithm::Blake2_256 => Some(sp_io::hashing::blake2_256(document)),
+            ManifestHashAlgorithm::Other => None,
+        }
+    }
+}
+
+/// The role an oracle fulfills for an escrow.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum OracleRole {
+    /// Evaluates the results and pays out workers.
+    Reputation,
+    /// Records the results of the job.
+    Recording,
+    /// Hands out tasks of the job to workers.
+    Exchange,
+    /// Any other party with a share in payouts, e.g. a sub-contracted annotation vendor.
+    Other,
+}
+
+/// The part an account plays in an escrow, by which escrows are indexed.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub enum EscrowParty {
+    /// Created the escrow and is refunded on cancel/abort.
+    Launcher,
+    /// Is an oracle of the escrow with the given role.
+    Oracle(OracleRole),
+}
+
+/// When the fixed fee of an oracle is released.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FixedFeeTiming {
+    /// Paid as soon as the escrow is funded sufficiently, before any payout.
+    Upfront,
+    /// Paid when the escrow is completed.
+    Completion,
+}
+
+/// A fee recipient of an escrow.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Oracle<AccountId, Balance> {
+    pub account: AccountId,
+    pub role: OracleRole,
+    /// The share of every payout transferred to the oracle.
+    pub fee: Percent,
+    /// A fixed amount paid to the oracle once per escrow, in addition to `fee`.
+    pub fixed_fee: Balance,
+    pub fixed_fee_timing: FixedFeeTiming,
+}
+
+/// The fees and remaining amounts a bulk payout would result in.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub struct PayoutPreview<AccountId, Balance> {
+    /// The percentage fee total for each oracle.
+    pub oracle_fees: Vec<(AccountId, Balance)>,
+    /// The protocol fee total going to `ProtocolFeeDestination`.
+    pub protocol_fee: Balance,
+    /// The amounts left for the recipients.
+    pub amounts: Vec<Balance>,
 }
 
 /// Points to where the results for an escrow are stored.
@@ -65,6 +295,39 @@ pub struct ResultInfo {
     results_hash: Vec<u8>,
 }
 
+/// An intermediate results checkpoint noted for an escrow.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub struct IntermediateResult<BlockNumber, AccountId> {
+    /// The block in which the results were noted.
+    pub block: BlockNumber,
+    /// Location of the intermediate results.
+    pub url: Vec<u8>,
+    pub hash: Vec<u8>,
+    /// The trusted handler that noted the results.
+    pub submitter: AccountId,
+}
+
+/// A recording oracle's commitment to final results that are yet to be revealed.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub struct ResultsCommitment<Hash, Moment> {
+    /// `Hashing(results_hash ++ salt)`.
+    pub commitment: Hash,
+    /// The point in time until which the results have to be revealed.
+    pub reveal_deadline: Moment,
+}
+
+/// The requester's verdict on the final results of an escrow.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub enum ResultsAcceptance<Moment> {
+    /// The results await review until the given deadline. Treated as accepted afterwards.
+    Pending(Moment),
+    /// The requester accepted the results.
+    Accepted,
+    /// The requester rejected the results, which opens a dispute.
+    /// New final results have to be stored to resolve it.
+    Rejected,
+}
+
 /// Defines the status of an escrow.
 ///
 /// Valid state transitions:
@@ -75,6 +338,7 @@ pub struct ResultInfo {
 ///    |           |
 ///    +-----------+----> Cancelled
 #[derive(Copy, Clone, Debug, Encode, Decode, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum EscrowStatus {
     /// An escrow is pending when created. Open for results and can be cancelled.
     Pending,
@@ -102,17 +366,36 @@ fn with_transaction_result<R>(
     })
 }
 
+/// Creates accounts and their signatures for benchmarking `store_signed_final_results`.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkSigner<AccountId, Signature> {
+    /// Create a new account with a signing key.
+    fn account() -> AccountId;
+    /// Sign `message` with the key of an `account` created by `account()`.
+    fn sign(account: &AccountId, message: &[u8]) -> Signature;
+}
+
 /// The weight info trait for `pallet_escrow`.
 pub trait WeightInfo {
     fn create_factory() -> Weight;
     fn create() -> Weight;
+    fn create_from_terms() -> Weight;
     fn add_trusted_handlers(h: u32) -> Weight;
     fn abort(h: u32, f: u32) -> Weight;
     fn cancel() -> Weight;
+    fn fund() -> Weight;
     fn complete() -> Weight;
     fn note_intermediate_results() -> Weight;
     fn store_final_results() -> Weight;
+    fn store_signed_final_results() -> Weight;
+    fn commit_results() -> Weight;
+    fn reveal_results() -> Weight;
+    fn flag_unrevealed() -> Weight;
+    fn accept_results() -> Weight;
+    fn reject_results() -> Weight;
     fn bulk_payout(b: u32) -> Weight;
+    fn set_protocol_fee() -> Weight;
+    fn report_manifest_check() -> Weight;
 }
 
 // default weights for tests
@@ -123,6 +406,9 @@ impl WeightInfo for () {
     fn create() -> Weight {
         0
     }
+    fn create_from_terms() -> Weight {
+        0
+    }
     fn add_trusted_handlers(_h: u32) -> Weight {
         0
     }
@@ -132,6 +418,9 @@ impl WeightInfo for () {
     fn cancel() -> Weight {
         0
     }
+    fn fund() -> Weight {
+        0
+    }
     fn complete() -> Weight {
         0
     }
@@ -141,12 +430,36 @@ impl WeightInfo for () {
     fn store_final_results() -> Weight {
         0
     }
+    fn store_signed_final_results() -> Weight {
+        0
+    }
+    fn commit_results() -> Weight {
+        0
+    }
+    fn reveal_results() -> Weight {
+        0
+    }
+    fn flag_unrevealed() -> Weight {
+        0
+    }
+    fn accept_results() -> Weight {
+        0
+    }
+    fn reject_results() -> Weight {
+        0
+    }
     fn bulk_payout(_b: u32) -> Weight {
         0
     }
+    fn set_protocol_fee() -> Weight {
+        0
+    }
+    fn report_manifest_check() -> Weight {
+        0
+    }
 }
 
-pub trait Trait: frame_system::Trait + timestamp::Trait {
+pub trait Trait: CreateSignedTransaction<Call<Self>> + frame_system::Trait + timestamp::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     /// The duration for which an escrow stays open.
     type StandardDuration: Get<Self::Moment>;
@@ -165,11 +478,52 @@ pub trait Trait: frame_system::Trait + timestamp::Trait {
     type HandlersLimit: Get<u32>;
     /// Maximum escrows per factory; to avoid an unbounded weight when querying.
     type EscrowsPerFactoryLimit: Get<u32>;
+    /// The maximum number of oracles (fee recipients) per escrow.
+    ///
+    /// Oracles become trusted handlers, so this should stay below `HandlersLimit`.
+    type OraclesLimit: Get<u32>;
+    /// The number of intermediate results kept per escrow. The oldest entry is dropped
+    /// when a new one is noted on a full history.
+    type IntermediateResultsLimit: Get<u32>;
+    /// Signature used by recording oracles to attest results relayed by another account.
+    type OracleSignature: Verify<Signer = Self::OracleSigner> + Parameter;
+    /// The signer of an `OracleSignature`, identifying an account.
+    type OracleSigner: IdentifyAccount<AccountId = Self::AccountId>;
+    /// The duration after a commitment within which the committed results have to be revealed.
+    type RevealWindow: Get<Self::Moment>;
+    /// The duration for which the requester can accept or reject stored final results.
+    ///
+    /// Results that were neither accepted nor rejected count as accepted afterwards.
+    type ResultsAcceptanceWindow: Get<Self::Moment>;
+    /// The origin allowed to change the protocol fee.
+    type ProtocolFeeOrigin: EnsureOrigin<Self::Origin>;
+    /// Receives the protocol fees taken from payouts, e.g. the treasury.
+    type ProtocolFeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// Looks up the public keys of oracles, which are recorded when creating an escrow.
+    type KeyProvider: KeyProvider<Self::AccountId>;
+    /// Whether the reputation and recording oracles need a public key to create an escrow.
+    type RequireOracleKeys: Get<bool>;
+    /// Creates recording oracle attestations for benchmarks.
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkSigner: BenchmarkSigner<Self::AccountId, Self::OracleSignature>;
+    /// The URL prefixes the offchain worker fetches manifests from, including the trailing slash.
+    ///
+    /// Manifests of new escrows are only checked while this is non-empty.
+    /// Escrows with a manifest hosted elsewhere fail the check.
+    type ManifestGateways: Get<Vec<Vec<u8>>>;
+    /// The maximum number of manifests the offchain worker checks per block.
+    type ManifestChecksPerBlock: Get<u32>;
+    /// The keys the offchain worker signs manifest check reports with.
+    type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+    /// The accounts trusted to report manifest checks, e.g. the validators.
+    type ManifestCheckers: Contains<Self::AccountId>;
     type WeightInfo: WeightInfo;
 }
 
 pub type BalanceOf<T> =
     <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+pub type NegativeImbalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
 
 decl_storage! {
     trait Store for Module<T: Trait> as Escrow {
@@ -180,15 +534,51 @@ decl_storage! {
         FactoryCounter get(fn factory_counter): FactoryId;
 
         /// Escrow storage. Stores configuration and state for an escorw.
-        Escrows get(fn escrow): map hasher(twox_64_concat) EscrowId => Option<EscrowInfo<T::Moment, T::AccountId>>;
+        pub Escrows get(fn escrow): map hasher(twox_64_concat) EscrowId => Option<EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>>;
 
         /// List of all currently active jobs.
         // For supporting factory API query.
         EscrowFactory get(fn escrow_factory): map hasher(twox_64_concat) FactoryId => Vec<EscrowId>;
 
+        /// Index of the escrows an account is a party of.
+        ///
+        /// Keyed by the big-endian escrow id, so the escrows of a party are stored in id order.
+        /// Entries are removed together with the escrow.
+        EscrowIndex get(fn escrow_index):
+            double_map hasher(blake2_128_concat) (T::AccountId, EscrowParty), hasher(identity) [u8; 16]
+            => ();
+
+        /// The most recent intermediate results for each escrow, oldest first.
+        ///
+        /// Bounded by `IntermediateResultsLimit`.
+        IntermediateResults get(fn intermediate_results):
+            map hasher(twox_64_concat) EscrowId => Vec<IntermediateResult<T::BlockNumber, T::AccountId>>;
+
         /// Results storage for each escrow.
         FinalResults get(fn final_results): map hasher(twox_64_concat) EscrowId => Option<ResultInfo>;
 
+        /// The number of times final results were stored for each escrow.
+        ///
+        /// Part of the payload signed for `store_signed_final_results`, so signatures cannot be replayed.
+        ResultsNonce get(fn results_nonce): map hasher(twox_64_concat) EscrowId => u32;
+
+        /// Commitments to final results that have not been revealed yet.
+        ResultsCommitments get(fn results_commitment):
+            double_map hasher(twox_64_concat) EscrowId, hasher(twox_64_concat) T::AccountId
+            => Option<ResultsCommitment<T::Hash, T::Moment>>;
+
+        /// The number of outstanding commitments for an escrow.
+        ///
+        /// Plaintext final results are refused while this is non-zero.
+        CommitmentsCount get(fn commitments_count): map hasher(twox_64_concat) EscrowId => u32;
+
+        /// The number of commitments an account failed to reveal in time.
+        MissedReveals get(fn missed_reveals): map hasher(twox_64_concat) T::AccountId => u32;
+
+        /// The requester's review of the final results for each escrow.
+        ResultsReview get(fn results_review):
+            map hasher(twox_64_concat) EscrowId => Option<ResultsAcceptance<T::Moment>>;
+
         /// The privileged accounts associated with an escrow.
         // TODO: consider changing value type to `()` to save space
         TrustedHandlers get(fn is_trusted_handler):
@@ -196,28 +586,96 @@ decl_storage! {
 
         /// The number of trusted handlers associated with an escrow.
         HandlersCount get(fn handlers_count): map hasher(twox_64_concat) EscrowId => u32;
+
+        /// The share of every payout taken as protocol fee, after the oracle fees.
+        ProtocolFee get(fn protocol_fee): Percent;
+
+        /// The escrows whose manifest awaits a check by the offchain worker.
+        PendingManifestChecks get(fn manifest_check_pending): map hasher(twox_64_concat) EscrowId => bool;
+
+        /// Whether the manifest of an escrow matched its `manifest_hash` when it was checked.
+        ///
+        /// Escrows with a mismatching manifest are closed for everything but cancel and abort.
+        pub ManifestChecks get(fn manifest_check): map hasher(twox_64_concat) EscrowId => Option<bool>;
+
+        /// The storage layout version, used to run migrations on runtime upgrades.
+        StorageVersion get(fn storage_version) build(|_| migrations::Releases::V2): migrations::Releases;
     }
 }
 
 decl_event!(
+    #[cfg_attr(feature = "std", derive(Serialize))]
     pub enum Event<T> where
         <T as frame_system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
     {
-        /// The escrow is in Pending status. \[escrow_id, creator, manifest_url, manifest_hash, escrow_account\]
-        Pending(EscrowId, AccountId, Vec<u8>, Vec<u8>, AccountId),
+        /// The escrow is in Pending status. \[escrow_id, creator, manifest_url, manifest_hash, escrow_account, oracles\]
+        Pending(EscrowId, AccountId, Vec<u8>, Vec<u8>, AccountId, Vec<Oracle<AccountId, Balance>>),
+        /// The escrow was funded. \[escrow_id, funder, amount\]
+        Funded(EscrowId, AccountId, Balance),
+        /// The escrow was cancelled and its balance refunded. \[escrow_id\]
+        Cancelled(EscrowId),
+        /// The escrow was aborted, refunded and its state cleared. \[escrow_id\]
+        Aborted(EscrowId),
+        /// The escrow was completed. \[escrow_id\]
+        Completed(EscrowId),
         /// Intermediate results can be found at the given url. \[escrow_id, url, hash\]
         IntermediateResults(EscrowId, Vec<u8>, Vec<u8>),
-        /// Bulk payout was executed. \[escrow_id\]
-        BulkPayout(EscrowId),
+        /// Bulk payout was executed. \[escrow_id, oracle_fees, protocol_fee\]
+        BulkPayout(EscrowId, Vec<(AccountId, Balance)>, Balance),
+        /// The fixed oracle fees of the escrow were paid. \[escrow_id, fixed_fees\]
+        FixedFeesPaid(EscrowId, Vec<(AccountId, Balance)>),
         /// Factory created successfully. \[escrow_id, creator\]
         FactoryCreated(FactoryId, AccountId),
+        /// Final results were committed to. \[escrow_id, committer\]
+        ResultsCommitted(EscrowId, AccountId),
+        /// Committed final results were revealed and stored. \[escrow_id, committer\]
+        ResultsRevealed(EscrowId, AccountId),
+        /// A commitment was not revealed in time and got flagged. \[escrow_id, committer\]
+        CommitmentUnrevealed(EscrowId, AccountId),
+        /// The requester accepted the final results. \[escrow_id\]
+        ResultsAccepted(EscrowId),
+        /// The requester rejected the final results and opened a dispute. \[escrow_id\]
+        ResultsRejected(EscrowId),
+        /// The protocol fee was changed. \[protocol_fee\]
+        ProtocolFeeSet(Percent),
+        /// The manifest of the escrow was checked against its hash. \[escrow_id, ok\]
+        ManifestChecked(EscrowId, bool),
+        /// The balance left after the budget was spent was refunded. \[escrow_id, canceller, amount\]
+        Refunded(EscrowId, AccountId, Balance),
     }
 );
 
+impl<AccountId, Balance> RawEvent<AccountId, Balance> {
+    /// The escrow the event is about, if any.
+    pub fn escrow_id(&self) -> Option<EscrowId> {
+        match self {
+            RawEvent::Pending(id, ..)
+            | RawEvent::Funded(id, ..)
+            | RawEvent::Cancelled(id)
+            | RawEvent::Aborted(id)
+            | RawEvent::Completed(id)
+            | RawEvent::IntermediateResults(id, ..)
+            | RawEvent::BulkPayout(id, ..)
+            | RawEvent::FixedFeesPaid(id, ..)
+            | RawEvent::ResultsCommitted(id, ..)
+            | RawEvent::ResultsRevealed(id, ..)
+            | RawEvent::CommitmentUnrevealed(id, ..)
+            | RawEvent::ResultsAccepted(id)
+            | RawEvent::ResultsRejected(id)
+            | RawEvent::ManifestChecked(id, _)
+            | RawEvent::Refunded(id, ..) => Some(*id),
+            RawEvent::FactoryCreated(..) | RawEvent::ProtocolFeeSet(..) => None,
+        }
+    }
+}
+
 decl_error! {
     pub enum Error for Module<T: Trait> {
-        /// The oracle stake given is invalid by exceeding 100%.
+        /// The oracle fees given are invalid by exceeding 100% in total.
         StakeOutOfBounds,
+        /// Tried to create an escrow with too many oracles.
+        TooManyOracles,
         /// A calculation overflowed.
         Overflow,
         /// The escrow specified cannot be found in storage.
@@ -245,7 +703,51 @@ decl_error! {
         /// Maximum escrows per factory limit reached.
         FactoryOutOfBounds,
         /// Factory does not exist with this Id.
-        FactoryDoesNotExist
+        FactoryDoesNotExist,
+        /// The account is not the requester (canceller) of the escrow.
+        NotRequester,
+        /// No final results have been stored for the escrow.
+        MissingResults,
+        /// The window for accepting or rejecting the results has passed.
+        AcceptanceWindowClosed,
+        /// The final results were already accepted and cannot be replaced.
+        ResultsAlreadyAccepted,
+        /// The final results are the ones already stored.
+        ResultsUnchanged,
+        /// The final results were neither accepted nor has the acceptance window passed.
+        ResultsNotAccepted,
+        /// There are outstanding commitments, results have to be revealed.
+        CommitRevealActive,
+        /// Final results were already published and not rejected, committing to results is pointless.
+        ResultsAlreadyPublished,
+        /// The account already has an outstanding commitment for the escrow.
+        AlreadyCommitted,
+        /// The account has no outstanding commitment for the escrow.
+        MissingCommitment,
+        /// The signature is not a valid attestation by the recording oracle.
+        InvalidSignature,
+        /// The revealed results do not match the commitment.
+        CommitmentMismatch,
+        /// The reveal window of the commitment has passed.
+        RevealWindowClosed,
+        /// The reveal window of the commitment has not passed yet.
+        RevealWindowOpen,
+        /// A reputation or recording oracle has not published a valid public key.
+        MissingOracleKey,
+        /// The job terms have no tasks, no price, invalid repeats or an overflowing budget.
+        InvalidTerms,
+        /// The payout would exceed the budget of the job terms.
+        BudgetExceeded,
+        /// The funding would exceed the budget and fixed oracle fees of the job terms.
+        FundingExceedsBudget,
+        /// The manifest of the escrow does not match its hash.
+        ManifestMismatch,
+        /// The manifest of the escrow is not awaiting a check.
+        ManifestCheckNotPending,
+        /// The account is not trusted to report manifest checks.
+        NotManifestChecker,
+        /// The manifest hash is not a digest of its algorithm.
+        InvalidManifestHash,
     }
 }
 
@@ -271,58 +773,39 @@ decl_module! {
         ///
         /// Oracles and sender will be set as trusted handlers.
         /// Sender is set as canceller of the escrow.
+        /// The public keys of the oracles are recorded, see `RequireOracleKeys`.
+        /// The manifest is checked against `manifest_hash` if `manifest_hash_algorithm` is supported,
+        /// in which case the hash has to be 32 bytes.
         /// Emits the escrow id with the `Pending` event.
         #[weight = <T as Trait>::WeightInfo::create()]
         pub fn create(origin,
             manifest_url: Vec<u8>,
             manifest_hash: Vec<u8>,
+            manifest_hash_algorithm: ManifestHashAlgorithm,
             factory_id: u128,
-            reputation_oracle: T::AccountId,
-            recording_oracle: T::AccountId,
-            // TODO: consider renaming to fee
-            reputation_oracle_stake: Percent,
-            recording_oracle_stake: Percent,
+            oracles: Vec<Oracle<T::AccountId, BalanceOf<T>>>,
         ) {
             let who = ensure_signed(origin)?;
-            ensure!(manifest_url.len() <= T::StringLimit::get(), Error::<T>::StringSize);
-            ensure!(manifest_hash.len() <= T::StringLimit::get(), Error::<T>::StringSize);
-            ensure!(<EscrowFactory>::contains_key(factory_id), Error::<T>::FactoryDoesNotExist);
-            let factory_escrows = <EscrowFactory>::get(factory_id);
-            ensure!(factory_escrows.len() as u32 < T::EscrowsPerFactoryLimit::get(), Error::<T>::FactoryOutOfBounds);
-            // This is fine as `100 + 100 < 256`, so no chance of overflow.
-            let total_stake = reputation_oracle_stake.deconstruct()
-                .saturating_add(recording_oracle_stake.deconstruct());
-            ensure!(total_stake <= 100, Error::<T>::StakeOutOfBounds);
-            let end_time = <timestamp::Module<T>>::get() + T::StandardDuration::get();
-
-            let id = Counter::get();
-            Counter::set(id + 1);
-
-            // Both oracles as well as the creator are trusted.
-            let trusted = vec![&recording_oracle, &reputation_oracle, &who];
-            HandlersCount::insert(id, trusted.len() as u32);
-            Self::do_add_trusted_handlers(id, trusted.into_iter());
-
-            let account = Self::account_id_for(id);
-            let new_escrow = EscrowInfo {
-                status: EscrowStatus::Pending,
-                end_time,
-                manifest_url: manifest_url.clone(),
-                manifest_hash: manifest_hash.clone(),
-                reputation_oracle,
-                recording_oracle,
-                reputation_oracle_stake,
-                recording_oracle_stake,
-                canceller: who.clone(),
-                account: account.clone(),
-                factory: factory_id,
-            };
-            <Escrows<T>>::insert(id, new_escrow);
-            <EscrowFactory>::mutate(factory_id, |list| {
-                list.push(id)
-            });
+            Self::do_create(who, manifest_url, manifest_hash, manifest_hash_algorithm, factory_id, oracles, None)?;
+        }
 
-            Self::deposit_event(RawEvent::Pending(id, who, manifest_url, manifest_hash, account));
+        /// Create a new escrow like `create`, with the economic terms of the job.
+        ///
+        /// Bulk payouts never exceed the budget of the terms, and the escrow is `Paid` once
+        /// the budget is spent.
+        #[weight = <T as Trait>::WeightInfo::create_from_terms()]
+        pub fn create_from_terms(origin,
+            manifest_url: Vec<u8>,
+            manifest_hash: Vec<u8>,
+            manifest_hash_algorithm: ManifestHashAlgorithm,
+            factory_id: u128,
+            oracles: Vec<Oracle<T::AccountId, BalanceOf<T>>>,
+            terms: JobTerms<BalanceOf<T>>,
+        ) {
+            let who = ensure_signed(origin)?;
+            ensure!(terms.is_valid(), Error::<T>::InvalidTerms);
+            let terms = Some(terms);
+            Self::do_create(who, manifest_url, manifest_hash, manifest_hash_algorithm, factory_id, oracles, terms)?;
         }
 
         /// Add the given accounts as trusted for escrow with `id`.
@@ -354,19 +837,30 @@ decl_module! {
             if balance > Zero::zero() {
                 T::Currency::transfer(&escrow.account, &escrow.canceller, balance, AllowDeath)?;
             }
+            Self::unindex_escrow(id, &escrow);
             <Escrows<T>>::remove(id);
+            <IntermediateResults<T>>::remove(id);
             FinalResults::remove(id);
+            ResultsNonce::remove(id);
+            <ResultsReview<T>>::remove(id);
+            <ResultsCommitments<T>>::remove_prefix(id);
+            CommitmentsCount::remove(id);
             <TrustedHandlers<T>>::remove_prefix(id);
             HandlersCount::remove(id);
+            PendingManifestChecks::remove(id);
+            ManifestChecks::remove(id);
 
             let mut escrows = <EscrowFactory>::take(escrow.factory);
             let index = escrows.binary_search(&id).map_err(|_| Error::<T>::MissingEscrow)?;
             escrows.remove(index);
             <EscrowFactory>::insert(escrow.factory, escrows);
+            Self::deposit_event(RawEvent::Aborted(id));
         }
 
         /// Cancel the escrow at `id` and refund any balance to the canceller defined in the escrow.
         ///
+        /// Fixed fees that were already paid up front are not refunded,
+        /// unpaid completion fees are.
         /// Requires trusted handler privileges.
         #[weight = <T as Trait>::WeightInfo::cancel()]
         fn cancel(origin, id: EscrowId) {
@@ -378,38 +872,91 @@ decl_module! {
             T::Currency::transfer(&escrow.account, &escrow.canceller, balance, AllowDeath)?;
             escrow.status = EscrowStatus::Cancelled;
             <Escrows<T>>::insert(id, escrow);
+            Self::deposit_event(RawEvent::Cancelled(id));
+        }
+
+        /// Transfer `amount` from the sender to the escrow at `id`.
+        ///
+        /// Pays the upfront fixed oracle fees once the escrow holds enough funds for them.
+        /// Fails with `FundingExceedsBudget` if an escrow with job terms would hold more than
+        /// what is left of its budget and fixed fees.
+        #[weight = <T as Trait>::WeightInfo::fund()]
+        fn fund(origin, id: EscrowId, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            with_transaction_result(|| -> DispatchResult {
+                let mut escrow = Self::get_open_escrow(id)?;
+                if let Some(cost) = Self::remaining_cost(&escrow) {
+                    let funded = Self::get_balance(&escrow).saturating_add(amount);
+                    ensure!(funded <= cost, Error::<T>::FundingExceedsBudget);
+                }
+                T::Currency::transfer(&who, &escrow.account, amount, AllowDeath)?;
+                if !escrow.upfront_fees_paid && Self::get_balance(&escrow) >= Self::upfront_fees(&escrow) {
+                    Self::pay_upfront_fees(id, &mut escrow)?;
+                    <Escrows<T>>::insert(id, escrow);
+                }
+                Self::deposit_event(RawEvent::Funded(id, who, amount));
+                Ok(())
+            })
         }
 
-        /// Set the escrow at `id` to be complete.
+        /// Set the escrow at `id` to be complete and release the completion fees of the oracles.
         ///
         /// Prohibits further editing or payouts of the escrow.
         /// Requires trusted handler privileges.
         // TODO: What is the intended use of `complete`?
         #[weight = <T as Trait>::WeightInfo::complete()]
-        fn complete(origin, id: EscrowId) {
+        fn complete(origin, id: EscrowId) -> DispatchResult {
             let mut escrow = Self::escrow(id).ok_or(Error::<T>::MissingEscrow)?;
             let _ = Self::ensure_trusted(origin, id)?;
             ensure!(escrow.end_time > <timestamp::Module<T>>::get(), Error::<T>::EscrowExpired);
             ensure!(escrow.status == EscrowStatus::Paid, Error::<T>::EscrowNotPaid);
-            escrow.status = EscrowStatus::Complete;
-            <Escrows<T>>::insert(id, escrow);
-            // TODO: consider cleaning up state here
+            ensure!(Self::results_accepted(id), Error::<T>::ResultsNotAccepted);
+            with_transaction_result(|| -> DispatchResult {
+                let fixed_fees = Self::fixed_fees(&escrow, FixedFeeTiming::Completion);
+                if !fixed_fees.is_empty() {
+                    for (oracle, fee) in fixed_fees.iter() {
+                        T::Currency::transfer(&escrow.account, oracle, *fee, AllowDeath)?;
+                    }
+                    Self::deposit_event(RawEvent::FixedFeesPaid(id, fixed_fees));
+                }
+                escrow.status = EscrowStatus::Complete;
+                <Escrows<T>>::insert(id, escrow);
+                Self::deposit_event(RawEvent::Completed(id));
+                // TODO: consider cleaning up state here
+                Ok(())
+            })
         }
 
-        /// Note intermediate results by emitting the `IntermediateResults` event.
+        /// Note intermediate results by storing them in the escrow's results history
+        /// and emitting the `IntermediateResults` event.
         ///
+        /// Drops the oldest entry if the history is full.
         /// Requires trusted handler privileges.
         #[weight = <T as Trait>::WeightInfo::note_intermediate_results()]
         fn note_intermediate_results(origin, id: EscrowId, url: Vec<u8>, hash: Vec<u8>) {
             ensure!(url.len() <= T::StringLimit::get(), Error::<T>::StringSize);
             ensure!(hash.len() <= T::StringLimit::get(), Error::<T>::StringSize);
-            let _ = Self::ensure_trusted(origin, id)?;
+            let who = Self::ensure_trusted(origin, id)?;
             let _ = Self::get_open_escrow(id)?;
+            let entry = IntermediateResult {
+                block: <frame_system::Module<T>>::block_number(),
+                url: url.clone(),
+                hash: hash.clone(),
+                submitter: who,
+            };
+            <IntermediateResults<T>>::mutate(id, |history| {
+                if history.len() as u32 >= T::IntermediateResultsLimit::get() {
+                    history.remove(0);
+                }
+                history.push(entry);
+            });
             Self::deposit_event(RawEvent::IntermediateResults(id, url, hash));
         }
 
         /// Store the url and hash of the final results in storage.
         ///
+        /// Opens the acceptance window for the requester.
+        /// Not possible while commitments to results are outstanding.
         /// Requires trusted handler privileges.
         #[weight = <T as Trait>::WeightInfo::store_final_results()]
         fn store_final_results(origin, id: EscrowId, url: Vec<u8>, hash: Vec<u8>) {
@@ -418,12 +965,144 @@ decl_module! {
             ensure!(hash.len() <= T::StringLimit::get(), Error::<T>::StringSize);
             let _ = Self::ensure_trusted(origin, id)?;
             let _ = Self::get_open_escrow(id)?;
-            FinalResults::insert(id, ResultInfo { results_url: url, results_hash: hash});
+            ensure!(Self::commitments_count(id) == 0, Error::<T>::CommitRevealActive);
+            Self::do_store_final_results(id, url, hash)?;
         }
 
-        /// Pay out `recipients` with `amounts`. Calculates and transfer oracle fees.
+        /// Store final results relayed on behalf of one of the escrow's recording oracles.
         ///
-        /// Sets the escrow to `Paid` if all balance is spent, otherwise to `Partial`.
+        /// `signature` is a recording oracle's signature over the SCALE-encoded
+        /// `(id, nonce, url, hash)`, where `nonce` is the current `ResultsNonce` of the escrow.
+        /// The sender does not need to be a trusted handler.
+        /// Opens the acceptance window for the requester.
+        #[weight = <T as Trait>::WeightInfo::store_signed_final_results()]
+        fn store_signed_final_results(origin,
+            id: EscrowId,
+            url: Vec<u8>,
+            hash: Vec<u8>,
+            signature: T::OracleSignature,
+        ) {
+            let _ = ensure_signed(origin)?;
+            ensure!(url.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+            ensure!(hash.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+            let escrow = Self::get_open_escrow(id)?;
+            ensure!(Self::commitments_count(id) == 0, Error::<T>::CommitRevealActive);
+            let payload = (id, Self::results_nonce(id), &url, &hash).encode();
+            let attested = escrow.oracles.iter()
+                .filter(|oracle| oracle.role == OracleRole::Recording)
+                .any(|oracle| signature.verify(&payload[..], &oracle.account));
+            ensure!(attested, Error::<T>::InvalidSignature);
+            Self::do_store_final_results(id, url, hash)?;
+        }
+
+        /// Commit to final results without publishing them.
+        ///
+        /// `commitment` is the hash of the SCALE-encoded `(id, committer, url, hash, salt)`
+        /// with a secret salt, see `results_commitment_for`.
+        /// The results have to be revealed with `reveal_results` within `RevealWindow`.
+        /// Possible until final results are published, or again after the requester rejected them.
+        /// Requires trusted handler privileges.
+        #[weight = <T as Trait>::WeightInfo::commit_results()]
+        fn commit_results(origin, id: EscrowId, commitment: T::Hash) {
+            let who = Self::ensure_trusted(origin, id)?;
+            let _ = Self::get_open_escrow(id)?;
+            ensure!(Self::results_replaceable(id), Error::<T>::ResultsAlreadyPublished);
+            ensure!(!<ResultsCommitments<T>>::contains_key(id, &who), Error::<T>::AlreadyCommitted);
+            let reveal_deadline = <timestamp::Module<T>>::get() + T::RevealWindow::get();
+            <ResultsCommitments<T>>::insert(id, &who, ResultsCommitment { commitment, reveal_deadline });
+            CommitmentsCount::mutate(id, |count| *count = count.saturating_add(1));
+            Self::deposit_event(RawEvent::ResultsCommitted(id, who));
+        }
+
+        /// Reveal previously committed final results and store them.
+        ///
+        /// Opens the acceptance window for the requester and drops the outstanding commitments
+        /// of other accounts. Replaces rejected final results, otherwise not possible once final
+        /// results were published.
+        /// Requires an outstanding commitment of the sender that matches `url`, `hash` and `salt`.
+        #[weight = <T as Trait>::WeightInfo::reveal_results()]
+        fn reveal_results(origin, id: EscrowId, url: Vec<u8>, hash: Vec<u8>, salt: Vec<u8>) {
+            let who = ensure_signed(origin)?;
+            ensure!(url.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+            ensure!(hash.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+            ensure!(salt.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+            let _ = Self::get_open_escrow(id)?;
+            ensure!(Self::results_replaceable(id), Error::<T>::ResultsAlreadyPublished);
+            let commitment = Self::results_commitment(id, &who).ok_or(Error::<T>::MissingCommitment)?;
+            ensure!(commitment.reveal_deadline >= <timestamp::Module<T>>::get(), Error::<T>::RevealWindowClosed);
+            ensure!(
+                commitment.commitment == Self::results_commitment_for(id, &who, &url, &hash, &salt),
+                Error::<T>::CommitmentMismatch
+            );
+            Self::do_store_final_results(id, url, hash)?;
+            <ResultsCommitments<T>>::remove_prefix(id);
+            CommitmentsCount::remove(id);
+            Self::deposit_event(RawEvent::ResultsRevealed(id, who));
+        }
+
+        /// Flag the commitment of `committer` as unrevealed after its reveal window has passed.
+        ///
+        /// Removes the commitment and counts it against the committer in `MissedReveals`.
+        /// Can be called by anyone.
+        #[weight = <T as Trait>::WeightInfo::flag_unrevealed()]
+        fn flag_unrevealed(origin, id: EscrowId, committer: T::AccountId) {
+            let _ = ensure_signed(origin)?;
+            let commitment = Self::results_commitment(id, &committer).ok_or(Error::<T>::MissingCommitment)?;
+            ensure!(commitment.reveal_deadline < <timestamp::Module<T>>::get(), Error::<T>::RevealWindowOpen);
+            <ResultsCommitments<T>>::remove(id, &committer);
+            CommitmentsCount::mutate(id, |count| *count = count.saturating_sub(1));
+            <MissedReveals<T>>::mutate(&committer, |count| *count = count.saturating_add(1));
+            Self::deposit_event(RawEvent::CommitmentUnrevealed(id, committer));
+        }
+
+        /// Accept the final results of the escrow at `id`.
+        ///
+        /// Can also be used to settle a dispute opened by `reject_results`.
+        /// Requires the sender to be the requester (canceller) of the escrow.
+        #[weight = <T as Trait>::WeightInfo::accept_results()]
+        fn accept_results(origin, id: EscrowId) {
+            let who = ensure_signed(origin)?;
+            let escrow = Self::escrow(id).ok_or(Error::<T>::MissingEscrow)?;
+            ensure!(who == escrow.canceller, Error::<T>::NotRequester);
+            ensure!(!matches!(escrow.status, EscrowStatus::Complete | EscrowStatus::Cancelled), Error::<T>::EscrowClosed);
+            ensure!(<ResultsReview<T>>::contains_key(id), Error::<T>::MissingResults);
+            <ResultsReview<T>>::insert(id, ResultsAcceptance::Accepted);
+            Self::deposit_event(RawEvent::ResultsAccepted(id));
+        }
+
+        /// Reject the final results of the escrow at `id`, opening a dispute.
+        ///
+        /// Blocks the final payout and completion until new results are stored and accepted.
+        /// Only possible within the acceptance window.
+        /// Requires the sender to be the requester (canceller) of the escrow.
+        #[weight = <T as Trait>::WeightInfo::reject_results()]
+        fn reject_results(origin, id: EscrowId) {
+            let who = ensure_signed(origin)?;
+            let escrow = Self::escrow(id).ok_or(Error::<T>::MissingEscrow)?;
+            ensure!(who == escrow.canceller, Error::<T>::NotRequester);
+            ensure!(!matches!(escrow.status, EscrowStatus::Complete | EscrowStatus::Cancelled), Error::<T>::EscrowClosed);
+            match Self::results_review(id) {
+                Some(ResultsAcceptance::Pending(deadline)) => {
+                    ensure!(deadline > <timestamp::Module<T>>::get(), Error::<T>::AcceptanceWindowClosed);
+                }
+                Some(_) => return Err(Error::<T>::AcceptanceWindowClosed.into()),
+                None => return Err(Error::<T>::MissingResults.into()),
+            }
+            <ResultsReview<T>>::insert(id, ResultsAcceptance::Rejected);
+            Self::deposit_event(RawEvent::ResultsRejected(id));
+        }
+
+        /// Pay out `recipients` with `amounts`. Calculates and transfer oracle fees
+        /// and the protocol fee.
+        ///
+        /// Pays the upfront fixed fees first if that did not happen at funding. The fixed fees
+        /// released at completion are held back and cannot be paid out to recipients.
+        /// Sets the escrow to `Paid` if all balance except for the completion fees
+        /// or the budget of its job terms is spent, otherwise to `Partial`.
+        /// The payout spending the remaining balance or budget requires accepted final results.
+        /// Fails with `BudgetExceeded` if the payouts would exceed the budget of the job terms.
+        /// The balance left once the budget is spent, except for the completion fees,
+        /// is refunded to the canceller.
         /// Requires trusted handler privileges.
         #[weight = <T as Trait>::WeightInfo::bulk_payout(recipients.len() as u32)]
         fn bulk_payout(origin,
@@ -434,7 +1113,11 @@ decl_module! {
             with_transaction_result(|| -> DispatchResult {
                 let mut escrow = Self::get_open_escrow(id)?;
                 let _ = Self::ensure_trusted(origin, id)?;
-                let balance = Self::get_balance(&escrow);
+                if !escrow.upfront_fees_paid {
+                    ensure!(Self::get_balance(&escrow) >= Self::upfront_fees(&escrow), Error::<T>::OutOfFunds);
+                    Self::pay_upfront_fees(id, &mut escrow)?;
+                }
+                let balance = Self::payable_balance(&escrow);
                 ensure!(balance > Zero::zero(), Error::<T>::OutOfFunds);
 
                 // make sure we have enough funds to pay
@@ -445,26 +1128,97 @@ decl_module! {
                 if balance < sum {
                     return Err(Error::<T>::OutOfFunds.into());
                 }
+                escrow.paid_out = escrow.paid_out.saturating_add(sum);
+                let budget_spent = match escrow.terms.as_ref() {
+                    Some(terms) => {
+                        let budget = terms.budget().ok_or(Error::<T>::Overflow)?;
+                        ensure!(escrow.paid_out <= budget, Error::<T>::BudgetExceeded);
+                        escrow.paid_out == budget
+                    }
+                    None => false,
+                };
                 // calculate fees
-                let (reputation_fee, recording_fee, final_amounts) = Self::finalize_payouts(&escrow, &amounts);
+                let (oracle_fees, protocol_fee, final_amounts) =
+                    Self::finalize_payouts(&escrow, Self::protocol_fee(), &amounts);
                 // transfer oracle fees
-                T::Currency::transfer(&escrow.account, &escrow.reputation_oracle, reputation_fee, AllowDeath)?;
-                T::Currency::transfer(&escrow.account, &escrow.recording_oracle, recording_fee, AllowDeath)?;
+                let mut paid_fees = Vec::with_capacity(oracle_fees.len());
+                for (oracle, fee) in escrow.oracles.iter().zip(oracle_fees.into_iter()) {
+                    T::Currency::transfer(&escrow.account, &oracle.account, fee, AllowDeath)?;
+                    paid_fees.push((oracle.account.clone(), fee));
+                }
+                if !protocol_fee.is_zero() {
+                    let imbalance = T::Currency::withdraw(
+                        &escrow.account,
+                        protocol_fee,
+                        WithdrawReason::Transfer.into(),
+                        AllowDeath,
+                    )?;
+                    T::ProtocolFeeDestination::on_unbalanced(imbalance);
+                }
                 Self::do_transfer_bulk(&escrow.account, &recipients, &final_amounts)?;
 
                 // set the escrow state according to payout
-                let balance = Self::get_balance(&escrow);
+                let balance = Self::payable_balance(&escrow);
                 if escrow.status == EscrowStatus::Pending {
                     escrow.status = EscrowStatus::Partial;
                 }
-                if balance == Zero::zero() && escrow.status == EscrowStatus::Partial {
+                if (balance == Zero::zero() || budget_spent) && escrow.status == EscrowStatus::Partial {
+                    ensure!(Self::results_accepted(id), Error::<T>::ResultsNotAccepted);
                     escrow.status = EscrowStatus::Paid;
                 }
+                // the escrow may have been sent more than the budget, e.g. by plain transfers
+                let refund = if budget_spent { balance } else { Zero::zero() };
+                if !refund.is_zero() {
+                    T::Currency::transfer(&escrow.account, &escrow.canceller, refund, AllowDeath)?;
+                }
+                let canceller = escrow.canceller.clone();
                 <Escrows<T>>::insert(id, escrow);
-                Self::deposit_event(RawEvent::BulkPayout(id));
+                Self::deposit_event(RawEvent::BulkPayout(id, paid_fees, protocol_fee));
+                if !refund.is_zero() {
+                    Self::deposit_event(RawEvent::Refunded(id, canceller, refund));
+                }
                 Ok(())
             })
         }
+
+        /// Set the share of every payout taken as protocol fee.
+        ///
+        /// Requires `ProtocolFeeOrigin`.
+        #[weight = <T as Trait>::WeightInfo::set_protocol_fee()]
+        fn set_protocol_fee(origin, fee: Percent) {
+            T::ProtocolFeeOrigin::ensure_origin(origin)?;
+            ProtocolFee::put(fee);
+            Self::deposit_event(RawEvent::ProtocolFeeSet(fee));
+        }
+
+        /// Record whether the manifest of the escrow at `id` matches its `manifest_hash`.
+        ///
+        /// An escrow failing the check cannot be funded, paid out or get results anymore,
+        /// it can only be cancelled or aborted.
+        /// Submitted by the offchain worker, signed with a `KEY_TYPE` key of a `ManifestCheckers` account.
+        #[weight = <T as Trait>::WeightInfo::report_manifest_check()]
+        fn report_manifest_check(origin, id: EscrowId, ok: bool) {
+            let who = ensure_signed(origin)?;
+            ensure!(T::ManifestCheckers::contains(&who), Error::<T>::NotManifestChecker);
+            ensure!(PendingManifestChecks::contains_key(id), Error::<T>::ManifestCheckNotPending);
+            PendingManifestChecks::remove(id);
+            ManifestChecks::insert(id, ok);
+            Self::deposit_event(RawEvent::ManifestChecked(id, ok));
+        }
+
+        /// Migrate the escrows stored by earlier runtimes, see `migrations`.
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get() == migrations::Releases::V1 {
+                migrations::migrate_to_v2::<T>()
+            } else {
+                T::DbWeight::get().reads(1)
+            }
+        }
+
+        /// Check the manifests of new escrows and report the results.
+        fn offchain_worker(block: T::BlockNumber) {
+            Self::check_manifests(block);
+        }
     }
 }
 
@@ -474,6 +1228,72 @@ impl<T: Trait> Module<T> {
         MODULE_ID.into_sub_account(id)
     }
 
+    /// Create an escrow for the factory, see `create`.
+    fn do_create(
+        who: T::AccountId,
+        manifest_url: Vec<u8>,
+        manifest_hash: Vec<u8>,
+        manifest_hash_algorithm: ManifestHashAlgorithm,
+        factory_id: FactoryId,
+        oracles: Vec<Oracle<T::AccountId, BalanceOf<T>>>,
+        terms: Option<JobTerms<BalanceOf<T>>>,
+    ) -> DispatchResult {
+        ensure!(manifest_url.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+        ensure!(manifest_hash.len() <= T::StringLimit::get(), Error::<T>::StringSize);
+        ensure!(
+            !manifest_hash_algorithm.is_supported() || manifest_hash.len() == 32,
+            Error::<T>::InvalidManifestHash
+        );
+        ensure!(<EscrowFactory>::contains_key(factory_id), Error::<T>::FactoryDoesNotExist);
+        let factory_escrows = <EscrowFactory>::get(factory_id);
+        ensure!((factory_escrows.len() as u32) < T::EscrowsPerFactoryLimit::get(), Error::<T>::FactoryOutOfBounds);
+        ensure!(oracles.len() as u32 <= T::OraclesLimit::get(), Error::<T>::TooManyOracles);
+        let total_fee = oracles.iter()
+            .fold(0u32, |total, oracle| total.saturating_add(oracle.fee.deconstruct() as u32));
+        ensure!(total_fee <= 100, Error::<T>::StakeOutOfBounds);
+        let oracle_keys = Self::oracle_keys_of(&oracles)?;
+        let end_time = <timestamp::Module<T>>::get() + T::StandardDuration::get();
+
+        let id = Counter::get();
+        Counter::set(id + 1);
+
+        // All oracles as well as the creator are trusted.
+        let trusted: Vec<&T::AccountId> = oracles.iter()
+            .map(|oracle| &oracle.account)
+            .chain(sp_std::iter::once(&who))
+            .collect();
+        HandlersCount::insert(id, trusted.len() as u32);
+        Self::do_add_trusted_handlers(id, trusted.into_iter());
+
+        let account = Self::account_id_for(id);
+        let new_escrow = EscrowInfo {
+            status: EscrowStatus::Pending,
+            end_time,
+            manifest_url: manifest_url.clone(),
+            manifest_hash: manifest_hash.clone(),
+            manifest_hash_algorithm,
+            oracles: oracles.clone(),
+            upfront_fees_paid: false,
+            canceller: who.clone(),
+            account: account.clone(),
+            factory: factory_id,
+            oracle_keys,
+            terms,
+            paid_out: Zero::zero(),
+        };
+        Self::index_escrow(id, &new_escrow);
+        <Escrows<T>>::insert(id, new_escrow);
+        if manifest_hash_algorithm.is_supported() && !T::ManifestGateways::get().is_empty() {
+            PendingManifestChecks::insert(id, true);
+        }
+        <EscrowFactory>::mutate(factory_id, |list| {
+            list.push(id)
+        });
+
+        Self::deposit_event(RawEvent::Pending(id, who, manifest_url, manifest_hash, account, oracles));
+        Ok(())
+    }
+
     /// Add the given accounts as trusted handlers (privileged accounts).
     pub(crate) fn do_add_trusted_handlers<'a, I>(id: EscrowId, trusted: I)
     where
@@ -495,15 +1315,170 @@ impl<T: Trait> Module<T> {
     }
 
     /// Get the balance associated with an escrow.
-    pub fn get_balance(escrow: &EscrowInfo<T::Moment, T::AccountId>) -> BalanceOf<T> {
+    pub fn get_balance(escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>) -> BalanceOf<T> {
         T::Currency::free_balance(&escrow.account)
     }
 
-    /// Get the escrow for `id` and check that it is not expired and
-    /// has `Pending` or `Partial` status.
+    /// Get the balance of an escrow that is not held back for fixed oracle fees.
+    pub fn payable_balance(escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>) -> BalanceOf<T> {
+        let mut reserved: BalanceOf<T> = Zero::zero();
+        for (_, fee) in Self::fixed_fees(escrow, FixedFeeTiming::Completion) {
+            reserved = reserved.saturating_add(fee);
+        }
+        if !escrow.upfront_fees_paid {
+            reserved = reserved.saturating_add(Self::upfront_fees(escrow));
+        }
+        Self::get_balance(escrow).saturating_sub(reserved)
+    }
+
+    /// Get the most an escrow with job terms still needs: the unspent budget and the fixed oracle
+    /// fees not paid yet. `None` for escrows without terms.
+    pub(crate) fn remaining_cost(escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>) -> Option<BalanceOf<T>> {
+        let budget = escrow.terms.as_ref()?.budget()?;
+        let mut cost = budget.saturating_sub(escrow.paid_out);
+        for (_, fee) in Self::fixed_fees(escrow, FixedFeeTiming::Completion) {
+            cost = cost.saturating_add(fee);
+        }
+        if !escrow.upfront_fees_paid {
+            cost = cost.saturating_add(Self::upfront_fees(escrow));
+        }
+        Some(cost)
+    }
+
+    /// Get the non-zero fixed fees of the escrow's oracles with the given `timing`.
+    pub(crate) fn fixed_fees(
+        escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>,
+        timing: FixedFeeTiming,
+    ) -> Vec<(T::AccountId, BalanceOf<T>)> {
+        escrow.oracles.iter()
+            .filter(|oracle| oracle.fixed_fee_timing == timing && !oracle.fixed_fee.is_zero())
+            .map(|oracle| (oracle.account.clone(), oracle.fixed_fee))
+            .collect()
+    }
+
+    /// Get the sum of the upfront fixed fees of the escrow's oracles.
+    pub(crate) fn upfront_fees(escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>) -> BalanceOf<T> {
+        let mut total: BalanceOf<T> = Zero::zero();
+        for (_, fee) in Self::fixed_fees(escrow, FixedFeeTiming::Upfront) {
+            total = total.saturating_add(fee);
+        }
+        total
+    }
+
+    /// Pay the upfront fixed fees of `escrow` and mark them as paid.
+    ///
+    /// Does not store the escrow.
+    ///
+    /// **Warning**: Will not revert the successful transfers on failure.
+    /// Use with transactional storage if that is desired.
+    pub(crate) fn pay_upfront_fees(
+        id: EscrowId,
+        escrow: &mut EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>,
+    ) -> DispatchResult {
+        let fixed_fees = Self::fixed_fees(escrow, FixedFeeTiming::Upfront);
+        if !fixed_fees.is_empty() {
+            for (oracle, fee) in fixed_fees.iter() {
+                T::Currency::transfer(&escrow.account, oracle, *fee, AllowDeath)?;
+            }
+            Self::deposit_event(RawEvent::FixedFeesPaid(id, fixed_fees));
+        }
+        escrow.upfront_fees_paid = true;
+        Ok(())
+    }
+
+    /// Get the oracles of the escrow at `id`.
+    pub fn oracles(id: EscrowId) -> Vec<Oracle<T::AccountId, BalanceOf<T>>> {
+        Self::escrow(id).map(|escrow| escrow.oracles).unwrap_or_default()
+    }
+
+    /// Get the public keys of the oracles recorded when the escrow at `id` was created.
+    pub fn oracle_keys(id: EscrowId) -> Vec<(T::AccountId, Vec<u8>)> {
+        Self::escrow(id).map(|escrow| escrow.oracle_keys).unwrap_or_default()
+    }
+
+    /// Look up the current public keys of `oracles`.
+    ///
+    /// Fails if `RequireOracleKeys` is set and a reputation or recording oracle has none.
+    pub(crate) fn oracle_keys_of(
+        oracles: &[Oracle<T::AccountId, BalanceOf<T>>],
+    ) -> Result<Vec<(T::AccountId, Vec<u8>)>, DispatchError> {
+        let mut keys: Vec<(T::AccountId, Vec<u8>)> = Vec::new();
+        for oracle in oracles {
+            if keys.iter().any(|(account, _)| account == &oracle.account) {
+                continue;
+            }
+            match T::KeyProvider::public_key(&oracle.account) {
+                Some(key) => keys.push((oracle.account.clone(), key)),
+                None => {
+                    let required = matches!(oracle.role, OracleRole::Reputation | OracleRole::Recording);
+                    ensure!(!(required && T::RequireOracleKeys::get()), Error::<T>::MissingOracleKey);
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    /// The parties of `escrow` by which it is indexed.
+    fn parties(
+        escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>,
+    ) -> impl Iterator<Item = (T::AccountId, EscrowParty)> + '_ {
+        sp_std::iter::once((escrow.canceller.clone(), EscrowParty::Launcher))
+            .chain(escrow.oracles.iter().map(|oracle| (oracle.account.clone(), EscrowParty::Oracle(oracle.role))))
+    }
+
+    /// Add the escrow at `id` to the index of its parties.
+    ///
+    /// Has to be called whenever the parties of an escrow are set.
+    pub(crate) fn index_escrow(id: EscrowId, escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>) {
+        for key in Self::parties(escrow) {
+            <EscrowIndex<T>>::insert(key, id.to_be_bytes(), ());
+        }
+    }
+
+    /// Remove the escrow at `id` from the index of its parties.
+    ///
+    /// Has to be called before the parties of an escrow change or the escrow is removed.
+    pub(crate) fn unindex_escrow(id: EscrowId, escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>) {
+        for key in Self::parties(escrow) {
+            <EscrowIndex<T>>::remove(key, id.to_be_bytes());
+        }
+    }
+
+    /// Get the ids of the escrows `who` is a party of as `party`, in ascending order.
+    ///
+    /// Returns at most `limit` ids greater than `start_after`,
+    /// so the last id of a page is the `start_after` of the next one.
+    pub fn escrows_of(
+        who: T::AccountId,
+        party: EscrowParty,
+        start_after: Option<EscrowId>,
+        limit: u32,
+    ) -> Vec<EscrowId> {
+        let key = (who, party);
+        // The index entries of the party share the key without the trailing id.
+        let first = <EscrowIndex<T>>::hashed_key_for(&key, start_after.unwrap_or_default().to_be_bytes());
+        let prefix = first[..first.len() - 16].to_vec();
+        let mut cursor = if start_after.is_some() { first } else { prefix.clone() };
+        let mut ids = Vec::new();
+        while ids.len() < limit as usize {
+            match sp_io::storage::next_key(&cursor) {
+                Some(next) if next.starts_with(&prefix) && next.len() == prefix.len() + 16 => {
+                    let mut id = [0u8; 16];
+                    id.copy_from_slice(&next[prefix.len()..]);
+                    ids.push(EscrowId::from_be_bytes(id));
+                    cursor = next;
+                }
+                _ => break,
+            }
+        }
+        ids
+    }
+
+    /// Get the escrow for `id` and check that it is not expired,
+    /// has `Pending` or `Partial` status and did not fail its manifest check.
     pub fn get_open_escrow(
         id: EscrowId,
-    ) -> Result<EscrowInfo<T::Moment, T::AccountId>, DispatchError> {
+    ) -> Result<EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>, DispatchError> {
         let escrow = Self::escrow(id).ok_or(Error::<T>::MissingEscrow)?;
         ensure!(
             escrow.end_time > <timestamp::Module<T>>::get(),
@@ -513,32 +1488,96 @@ impl<T: Trait> Module<T> {
             matches!(escrow.status, EscrowStatus::Pending | EscrowStatus::Partial),
             Error::<T>::EscrowClosed
         );
+        ensure!(Self::manifest_check(id) != Some(false), Error::<T>::ManifestMismatch);
         Ok(escrow)
     }
 
-    /// Determine the oracle fees for the given `escrow` and `amounts`.
+    /// Store the final results for `id`, open the acceptance window and bump the `ResultsNonce`.
+    ///
+    /// Fails if the current results were accepted by the requester or by letting the
+    /// acceptance window pass, or if the results are the ones already stored.
+    pub(crate) fn do_store_final_results(id: EscrowId, url: Vec<u8>, hash: Vec<u8>) -> DispatchResult {
+        ensure!(!Self::results_accepted(id), Error::<T>::ResultsAlreadyAccepted);
+        let results = ResultInfo { results_url: url, results_hash: hash };
+        ensure!(Self::final_results(id).as_ref() != Some(&results), Error::<T>::ResultsUnchanged);
+        FinalResults::insert(id, results);
+        ResultsNonce::mutate(id, |nonce| *nonce = nonce.wrapping_add(1));
+        let deadline = <timestamp::Module<T>>::get() + T::ResultsAcceptanceWindow::get();
+        <ResultsReview<T>>::insert(id, ResultsAcceptance::Pending(deadline));
+        Ok(())
+    }
+
+    /// Compute the commitment of `committer` to the results `url` and `hash` for the escrow at `id`.
+    ///
+    /// Binding the escrow and the committer keeps others from copying a commitment seen on-chain.
+    pub fn results_commitment_for(
+        id: EscrowId,
+        committer: &T::AccountId,
+        url: &[u8],
+        hash: &[u8],
+        salt: &[u8],
+    ) -> T::Hash {
+        T::Hashing::hash(&(id, committer, url, hash, salt).encode())
+    }
+
+    /// Whether new final results can be committed to for the escrow at `id`,
+    /// i.e. there are none yet or the requester rejected them.
+    fn results_replaceable(id: EscrowId) -> bool {
+        !FinalResults::contains_key(id) || Self::results_review(id) == Some(ResultsAcceptance::Rejected)
+    }
+
+    /// Whether the final results of the escrow at `id` were accepted by the requester,
+    /// either explicitly or by letting the acceptance window pass.
+    pub fn results_accepted(id: EscrowId) -> bool {
+        match Self::results_review(id) {
+            Some(ResultsAcceptance::Accepted) => true,
+            Some(ResultsAcceptance::Pending(deadline)) => deadline <= <timestamp::Module<T>>::get(),
+            Some(ResultsAcceptance::Rejected) | None => false,
+        }
+    }
+
+    /// Preview the fees and remaining amounts of paying out `amounts` from the escrow at `id`.
+    pub fn preview_payout(
+        id: EscrowId,
+        amounts: Vec<BalanceOf<T>>,
+    ) -> Option<PayoutPreview<T::AccountId, BalanceOf<T>>> {
+        let escrow = Self::escrow(id)?;
+        let (fee_totals, protocol_fee, amounts) = Self::finalize_payouts(&escrow, Self::protocol_fee(), &amounts);
+        let oracle_fees = escrow.oracles.into_iter()
+            .map(|oracle| oracle.account)
+            .zip(fee_totals.into_iter())
+            .collect();
+        Some(PayoutPreview { oracle_fees, protocol_fee, amounts })
+    }
+
+    /// Determine the percentage oracle fees and `protocol_fee` for the given `escrow` and `amounts`.
+    ///
+    /// The protocol fee is taken on top of the oracle fees and capped at what is left of an amount.
+    /// Fixed fees are settled separately and not included.
+    /// Returns the fee total for each oracle, in the order of `escrow.oracles`,
+    /// the protocol fee total and the amounts left for the recipients.
     pub(crate) fn finalize_payouts(
-        escrow: &EscrowInfo<T::Moment, T::AccountId>,
+        escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>,
+        protocol_fee: Percent,
         amounts: &[BalanceOf<T>],
-    ) -> (BalanceOf<T>, BalanceOf<T>, Vec<BalanceOf<T>>) {
-        let mut reputation_fee_total: BalanceOf<T> = Zero::zero();
-        let reputation_stake = escrow.reputation_oracle_stake;
-        let mut recording_fee_total: BalanceOf<T> = Zero::zero();
-        let recording_stake = escrow.recording_oracle_stake;
+    ) -> (Vec<BalanceOf<T>>, BalanceOf<T>, Vec<BalanceOf<T>>) {
+        let mut protocol_total: BalanceOf<T> = Zero::zero();
+        let mut fee_totals: Vec<BalanceOf<T>> = vec![Zero::zero(); escrow.oracles.len()];
         let final_amounts = amounts
             .iter()
             .map(|amount| {
-                let reputation_fee = reputation_stake.mul_floor(*amount);
-                let recording_fee = recording_stake.mul_floor(*amount);
-                let amount_without_fee = amount
-                    .saturating_sub(reputation_fee)
-                    .saturating_sub(recording_fee);
-                reputation_fee_total = reputation_fee_total.saturating_add(reputation_fee);
-                recording_fee_total = recording_fee_total.saturating_add(recording_fee);
-                amount_without_fee
+                let mut amount_without_fee = *amount;
+                for (oracle, total) in escrow.oracles.iter().zip(fee_totals.iter_mut()) {
+                    let fee = oracle.fee.mul_floor(*amount);
+                    amount_without_fee = amount_without_fee.saturating_sub(fee);
+                    *total = total.saturating_add(fee);
+                }
+                let fee = protocol_fee.mul_floor(*amount).min(amount_without_fee);
+                protocol_total = protocol_total.saturating_add(fee);
+                amount_without_fee.saturating_sub(fee)
             })
             .collect();
-        (reputation_fee_total, recording_fee_total, final_amounts)
+        (fee_totals, protocol_total, final_amounts)
     }
 
     /// Do a bulk transfer from the given account to the recepients.
@@ -570,4 +1609,114 @@ impl<T: Trait> Module<T> {
         }
         Ok(())
     }
+
+    /// Check the manifests of up to `ManifestChecksPerBlock` escrows awaiting a check
+    /// and submit the results as signed `report_manifest_check` transactions.
+    ///
+    /// Does nothing unless the keystore holds a `KEY_TYPE` key of a `ManifestCheckers` account.
+    /// Escrows with a report submitted within the last `MANIFEST_CHECK_RETRY` blocks are skipped.
+    /// Manifests that could not be fetched are tried again in the next block, see `note_failed_fetch`.
+    fn check_manifests(block: T::BlockNumber) {
+        let signer = match Self::manifest_check_signer() {
+            Some(signer) => signer,
+            None => return,
+        };
+        let retry: T::BlockNumber = MANIFEST_CHECK_RETRY.into();
+        let mut checked = 0;
+        for (id, _) in PendingManifestChecks::iter() {
+            if checked >= T::ManifestChecksPerBlock::get() {
+                break;
+            }
+            let key = [MANIFEST_CHECK_KEY, &id.encode()[..]].concat();
+            let submitted = StorageValueRef::persistent(&key);
+            if let Some(Some(at)) = submitted.get::<T::BlockNumber>() {
+                if block < at.saturating_add(retry) {
+                    continue;
+                }
+            }
+            // Closed escrows cannot be used with a mismatching manifest anyway.
+            let escrow = match Self::escrow(id) {
+                Some(escrow) if matches!(escrow.status, EscrowStatus::Pending | EscrowStatus::Partial) => escrow,
+                _ => continue,
+            };
+            checked += 1;
+            let ok = match Self::check_manifest(&escrow) {
+                Ok(ok) => ok,
+                Err(e) if Self::note_failed_fetch(id, &e) => {
+                    debug::warn!("Giving up fetching the manifest of escrow {}: {:?}", id, e);
+                    false
+                }
+                Err(e) => {
+                    debug::warn!("Failed to fetch the manifest of escrow {}: {:?}", id, e);
+                    continue;
+                }
+            };
+            match signer.send_signed_transaction(|_| Call::report_manifest_check(id, ok)) {
+                Some((_, Ok(()))) => submitted.set(&block),
+                _ => debug::warn!("Failed to submit the manifest check of escrow {}", id),
+            }
+        }
+    }
+
+    /// The signer of manifest check reports: the first local `KEY_TYPE` key of a `ManifestCheckers` account.
+    fn manifest_check_signer() -> Option<Signer<T, T::AuthorityId>> {
+        let public = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::all()
+            .into_iter()
+            .map(|key| -> T::Public {
+                <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic::from(key).into()
+            })
+            .find(|public| T::ManifestCheckers::contains(&public.clone().into_account()))?;
+        Some(Signer::<T, T::AuthorityId>::any_account().with_filter(vec![public]))
+    }
+
+    /// Count a failed attempt to fetch the manifest of escrow `id`.
+    ///
+    /// Returns whether to give up, which is the case once the gateway answered without the
+    /// manifest `MANIFEST_FETCH_ATTEMPTS` times. Timeouts and IO errors are retried indefinitely,
+    /// as they may be caused by the node itself.
+    pub(crate) fn note_failed_fetch(id: EscrowId, error: &http::Error) -> bool {
+        if *error != http::Error::Unknown {
+            return false;
+        }
+        let key = [MANIFEST_FETCH_ATTEMPTS_KEY, &id.encode()[..]].concat();
+        let attempts = StorageValueRef::persistent(&key);
+        let failed = attempts.get::<u32>().flatten().unwrap_or(0).saturating_add(1);
+        attempts.set(&failed);
+        failed >= MANIFEST_FETCH_ATTEMPTS
+    }
+
+    /// Fetch the manifest of `escrow` and check it against the `manifest_hash`.
+    ///
+    /// Manifests hosted outside of `ManifestGateways` or exceeding `MANIFEST_SIZE_LIMIT`
+    /// fail the check.
+    fn check_manifest(
+        escrow: &EscrowInfo<T::Moment, T::AccountId, BalanceOf<T>>,
+    ) -> Result<bool, http::Error> {
+        let allowed = T::ManifestGateways::get().iter()
+            .any(|gateway| escrow.manifest_url.starts_with(gateway));
+        let url = match sp_std::str::from_utf8(&escrow.manifest_url) {
+            Ok(url) if allowed => url,
+            _ => return Ok(false),
+        };
+        let document = Self::fetch_manifest(url)?;
+        let matches = escrow.manifest_hash_algorithm.digest(&document)
+            .map_or(false, |hash| hash[..] == escrow.manifest_hash[..]);
+        Ok(document.len() <= MANIFEST_SIZE_LIMIT && matches)
+    }
+
+    /// Fetch the document at `url`, reading at most one byte more than `MANIFEST_SIZE_LIMIT`.
+    ///
+    /// Fails with `http::Error::Unknown` if the gateway does not answer with status 200.
+    fn fetch_manifest(url: &str) -> Result<Vec<u8>, http::Error> {
+        let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(MANIFEST_FETCH_TIMEOUT));
+        let pending = http::Request::get(url)
+            .deadline(deadline)
+            .send()
+            .map_err(|_| http::Error::IoError)?;
+        let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+        if response.code != 200 {
+            return Err(http::Error::Unknown);
+        }
+        Ok(response.body().take(MANIFEST_SIZE_LIMIT + 1).collect())
+    }
 }
@@ -0,0 +1,85 @@
+//! Storage migrations of the escrow pallet.
+
+use super::*;
+use frame_support::storage::IterableStorageMap;
+
+/// Storage layout versions of the escrow pallet.
+#[derive(Clone, Copy, Encode, Decode, Debug, PartialEq, Eq)]
+pub enum Releases {
+    /// Escrows with a single reputation and recording oracle.
+    V1,
+    /// Escrows with a list of oracles, fixed fees, oracle keys, job terms and a manifest hash algorithm.
+    V2,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
+/// The escrow layout of `Releases::V1`.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub struct EscrowInfoV1<Moment, AccountId> {
+    pub status: EscrowStatus,
+    pub end_time: Moment,
+    pub manifest_url: Vec<u8>,
+    pub manifest_hash: Vec<u8>,
+    pub reputation_oracle: AccountId,
+    pub recording_oracle: AccountId,
+    pub reputation_oracle_stake: Percent,
+    pub recording_oracle_stake: Percent,
+    pub canceller: AccountId,
+    pub account: AccountId,
+    pub factory: FactoryId,
+}
+
+/// Migrate the escrows from `Releases::V1` to `Releases::V2`.
+///
+/// The reputation and recording oracles become the oracle list, without fixed fees or recorded keys,
+/// and the escrows are added to the index of their parties. Their manifest hash algorithm is
+/// unknown, so their manifests are not checked. Final results stored before the
+/// acceptance window existed count as accepted, as they could already be paid out.
+/// So do the results of paid and complete escrows, which may have been paid out without them.
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+    let migrated = sp_std::cell::Cell::new(0 as Weight);
+    <Escrows<T>>::translate::<EscrowInfoV1<T::Moment, T::AccountId>, _>(|id, old| {
+        let oracle = |account, role, fee| Oracle {
+            account,
+            role,
+            fee,
+            fixed_fee: Zero::zero(),
+            fixed_fee_timing: FixedFeeTiming::Completion,
+        };
+        let escrow = EscrowInfo {
+            status: old.status,
+            end_time: old.end_time,
+            manifest_url: old.manifest_url,
+            manifest_hash: old.manifest_hash,
+            manifest_hash_algorithm: ManifestHashAlgorithm::Other,
+            oracles: vec![
+                oracle(old.reputation_oracle, OracleRole::Reputation, old.reputation_oracle_stake),
+                oracle(old.recording_oracle, OracleRole::Recording, old.recording_oracle_stake),
+            ],
+            upfront_fees_paid: true,
+            canceller: old.canceller,
+            account: old.account,
+            factory: old.factory,
+            oracle_keys: Vec::new(),
+            terms: None,
+            paid_out: Zero::zero(),
+        };
+        <Module<T>>::index_escrow(id, &escrow);
+        let paid = matches!(escrow.status, EscrowStatus::Paid | EscrowStatus::Complete);
+        if paid || FinalResults::contains_key(id) {
+            <ResultsReview<T>>::insert(id, ResultsAcceptance::Accepted);
+        }
+        migrated.set(migrated.get() + 1);
+        Some(escrow)
+    });
+    let migrated = migrated.get();
+    StorageVersion::put(Releases::V2);
+    // Per escrow: the escrow and its results are read, the escrow, its review and
+    // the index entries of its launcher and two oracles are written.
+    T::DbWeight::get().reads_writes(2 * migrated + 1, 5 * migrated + 1)
+}
@@ -1,10 +1,12 @@
-use crate::{Module, Trait};
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use crate::{Call, Module, Trait};
+use codec::{Decode, Encode};
+use frame_support::{impl_outer_origin, parameter_types, traits::{Contains, Get}, weights::Weight};
 use frame_system as system;
-use sp_core::H256;
+use sp_core::{sr25519, H256};
+use std::{cell::RefCell, collections::BTreeMap};
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Lazy, Verify},
 	Perbill,
 };
 
@@ -88,6 +90,175 @@ parameter_types! {
 	pub const BulkAccountsLimit: usize = 10;
 	pub const BulkBalanceLimit: Balance = 1_000_000_000;
 	pub const HandlersLimit: u32 = 10;
+	pub const EscrowsPerFactoryLimit: u32 = 20;
+	pub const OraclesLimit: u32 = 4;
+	pub const IntermediateResultsLimit: u32 = 3;
+	pub const RevealWindow: Moment = 50;
+	pub const ResultsAcceptanceWindow: Moment = 100;
+	pub const ManifestChecksPerBlock: u32 = 2;
+}
+
+/// Signature mock: valid for the named signer and exactly the carried message.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub struct TestSignature(pub AccountId, pub Vec<u8>);
+
+pub struct TestSigner(AccountId);
+
+impl IdentifyAccount for TestSigner {
+	type AccountId = AccountId;
+	fn into_account(self) -> AccountId {
+		self.0
+	}
+}
+
+impl Verify for TestSignature {
+	type Signer = TestSigner;
+	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId) -> bool {
+		self.0 == *signer && self.1 == msg.get()
+	}
+}
+
+/// Benchmark signer mock: signs for a fixed account.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkSigner;
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkSigner<AccountId, TestSignature> for MockBenchmarkSigner {
+	fn account() -> AccountId {
+		1_000_000
+	}
+
+	fn sign(account: &AccountId, message: &[u8]) -> TestSignature {
+		TestSignature(*account, message.to_vec())
+	}
+}
+
+thread_local! {
+	static PUBLIC_KEYS: RefCell<BTreeMap<AccountId, Vec<u8>>> = RefCell::new(BTreeMap::new());
+	static REQUIRE_ORACLE_KEYS: RefCell<bool> = RefCell::new(false);
+	static MANIFEST_GATEWAYS: RefCell<Vec<Vec<u8>>> = RefCell::new(vec![]);
+	static MANIFEST_CHECKERS: RefCell<Vec<AccountId>> = RefCell::new(vec![]);
+}
+
+/// Key provider mock: every published key is valid.
+pub struct MockKeyProvider;
+impl pallet_kvstore::KeyProvider<AccountId> for MockKeyProvider {
+	fn public_key(who: &AccountId) -> Option<Vec<u8>> {
+		PUBLIC_KEYS.with(|keys| keys.borrow().get(who).cloned())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_public_key(who: &AccountId, key: Vec<u8>) {
+		publish_key(*who, key);
+	}
+}
+
+pub fn publish_key(who: AccountId, key: Vec<u8>) {
+	PUBLIC_KEYS.with(|keys| keys.borrow_mut().insert(who, key));
+}
+
+pub struct RequireOracleKeys;
+impl Get<bool> for RequireOracleKeys {
+	fn get() -> bool {
+		REQUIRE_ORACLE_KEYS.with(|require| *require.borrow())
+	}
+}
+
+pub fn set_require_oracle_keys(require: bool) {
+	REQUIRE_ORACLE_KEYS.with(|r| *r.borrow_mut() = require);
+}
+
+pub struct ManifestGateways;
+impl Get<Vec<Vec<u8>>> for ManifestGateways {
+	fn get() -> Vec<Vec<u8>> {
+		MANIFEST_GATEWAYS.with(|gateways| gateways.borrow().clone())
+	}
+}
+
+pub fn set_manifest_gateways(gateways: Vec<Vec<u8>>) {
+	MANIFEST_GATEWAYS.with(|g| *g.borrow_mut() = gateways);
+}
+
+pub struct ManifestCheckers;
+impl Contains<AccountId> for ManifestCheckers {
+	fn sorted_members() -> Vec<AccountId> {
+		MANIFEST_CHECKERS.with(|checkers| checkers.borrow().clone())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add(who: &AccountId) {
+		add_manifest_checker(*who);
+	}
+}
+
+pub fn add_manifest_checker(who: AccountId) {
+	MANIFEST_CHECKERS.with(|checkers| {
+		let mut checkers = checkers.borrow_mut();
+		if let Err(index) = checkers.binary_search(&who) {
+			checkers.insert(index, who);
+		}
+	});
+}
+
+/// Public key mock for signed transactions: the account is made of the first 16 bytes of the key.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TestPublic(pub sr25519::Public);
+
+impl IdentifyAccount for TestPublic {
+	type AccountId = AccountId;
+	fn into_account(self) -> AccountId {
+		let key: &[u8] = self.0.as_ref();
+		let mut account = [0; 16];
+		account.copy_from_slice(&key[..16]);
+		AccountId::from_le_bytes(account)
+	}
+}
+
+impl From<sr25519::Public> for TestPublic {
+	fn from(public: sr25519::Public) -> Self {
+		TestPublic(public)
+	}
+}
+
+impl From<TestPublic> for sr25519::Public {
+	fn from(public: TestPublic) -> Self {
+		public.0
+	}
+}
+
+pub struct TestAuthorityId;
+impl system::offchain::AppCrypto<TestPublic, sr25519::Signature> for TestAuthorityId {
+	type RuntimeAppPublic = crate::crypto::Public;
+	type GenericSignature = sr25519::Signature;
+	type GenericPublic = sr25519::Public;
+}
+
+pub type Extrinsic = TestXt<Call<Test>, ()>;
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+	Call<Test>: From<C>,
+{
+	type OverarchingCall = Call<Test>;
+	type Extrinsic = Extrinsic;
+}
+
+impl system::offchain::SigningTypes for Test {
+	type Public = TestPublic;
+	type Signature = sr25519::Signature;
+}
+
+impl<C> system::offchain::CreateSignedTransaction<C> for Test
+where
+	Call<Test>: From<C>,
+{
+	fn create_transaction<A: system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call<Test>,
+		_public: TestPublic,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(Call<Test>, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
 }
 
 impl Trait for Test {
@@ -98,6 +269,23 @@ impl Trait for Test {
 	type BulkBalanceLimit = BulkBalanceLimit;
 	type Currency = pallet_balances::Module<Test>;
 	type HandlersLimit = HandlersLimit;
+	type EscrowsPerFactoryLimit = EscrowsPerFactoryLimit;
+	type OraclesLimit = OraclesLimit;
+	type IntermediateResultsLimit = IntermediateResultsLimit;
+	type OracleSignature = TestSignature;
+	type OracleSigner = TestSigner;
+	type RevealWindow = RevealWindow;
+	type ResultsAcceptanceWindow = ResultsAcceptanceWindow;
+	type ProtocolFeeOrigin = system::EnsureRoot<AccountId>;
+	type ProtocolFeeDestination = ();
+	type KeyProvider = MockKeyProvider;
+	type RequireOracleKeys = RequireOracleKeys;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkSigner = MockBenchmarkSigner;
+	type ManifestGateways = ManifestGateways;
+	type ManifestChecksPerBlock = ManifestChecksPerBlock;
+	type AuthorityId = TestAuthorityId;
+	type ManifestCheckers = ManifestCheckers;
 	type WeightInfo = ();
 }
 
@@ -108,6 +296,10 @@ pub type Timestamp = pallet_timestamp::Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	PUBLIC_KEYS.with(|keys| keys.borrow_mut().clear());
+	set_require_oracle_keys(false);
+	set_manifest_gateways(vec![]);
+	MANIFEST_CHECKERS.with(|checkers| checkers.borrow_mut().clear());
 	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	pallet_balances::GenesisConfig::<Test> {
 		balances: vec![(1, 1_000)],
@@ -1,14 +1,31 @@
+use codec::{Decode, Encode};
 use crate::{
-	mock::*, Error, EscrowId, EscrowInfo, EscrowStatus, Escrows, RawEvent, ResultInfo, Trait, TrustedHandlers,
+	migrations::{EscrowInfoV1, Releases},
+	mock::*, Call, Counter, Error, EscrowId, EscrowInfo, EscrowParty, EscrowStatus, Escrows, FinalResults, FixedFeeTiming,
+	IntermediateResult, JobTerms, ManifestHashAlgorithm, Oracle, OracleRole, PayoutPreview, RawEvent, ResultInfo,
+	ResultsAcceptance, Trait, TrustedHandlers, KEY_TYPE,
 };
 use frame_support::{
 	assert_noop, assert_ok,
 	dispatch::{DispatchError, DispatchResult},
-	storage::{StorageDoubleMap, StorageMap},
-	traits::Currency,
+	storage::{unhashed, StorageDoubleMap, StorageMap, StorageValue},
+	traits::{Currency, Get, OffchainWorker, OnRuntimeUpgrade},
+	weights::RuntimeDbWeight,
 };
 use frame_system::EventRecord;
-use sp_runtime::Percent;
+use sp_core::{
+	offchain::{
+		testing::{PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+		OffchainExt, TransactionPoolExt,
+	},
+	testing::KeyStore,
+	traits::KeystoreExt,
+};
+use sp_runtime::{
+	offchain::http,
+	traits::{BlakeTwo256, Hash, IdentifyAccount},
+	Percent,
+};
 
 #[derive(Debug, Default)]
 struct EscrowBuilder {
@@ -17,10 +34,12 @@ struct EscrowBuilder {
 	canceller: Option<AccountId>,
 	manifest_url: Option<Vec<u8>>,
 	manifest_hash: Option<Vec<u8>>,
+	manifest_hash_algorithm: Option<ManifestHashAlgorithm>,
 	reputation_oracle: Option<AccountId>,
 	recording_oracle: Option<AccountId>,
 	reputation_oracle_stake: Option<Percent>,
 	recording_oracle_stake: Option<Percent>,
+	other_oracles: Vec<Oracle<AccountId, Balance>>,
 	account: Option<AccountId>,
 	factory: u128,
 }
@@ -60,6 +79,28 @@ impl EscrowBuilder {
 		self
 	}
 
+	pub fn oracle(self, account: AccountId, role: OracleRole, fee: Percent) -> Self {
+		self.oracle_with_fixed_fee(account, role, fee, 0, FixedFeeTiming::Completion)
+	}
+
+	pub fn oracle_with_fixed_fee(
+		mut self,
+		account: AccountId,
+		role: OracleRole,
+		fee: Percent,
+		fixed_fee: Balance,
+		fixed_fee_timing: FixedFeeTiming,
+	) -> Self {
+		self.other_oracles.push(Oracle {
+			account,
+			role,
+			fee,
+			fixed_fee,
+			fixed_fee_timing,
+		});
+		self
+	}
+
 	pub fn manifest_url(mut self, u: Vec<u8>) -> Self {
 		self.manifest_url = Some(u);
 		self
@@ -70,15 +111,41 @@ impl EscrowBuilder {
 		self
 	}
 
-	pub fn build(self) -> EscrowInfo<Moment, AccountId> {
+	pub fn manifest_hash_algorithm(mut self, a: ManifestHashAlgorithm) -> Self {
+		self.manifest_hash_algorithm = Some(a);
+		self
+	}
+
+	pub fn build(self) -> EscrowInfo<Moment, AccountId, Balance> {
 		let status = self.status.unwrap_or(EscrowStatus::Pending);
 		let canceller = self.canceller.unwrap_or(1);
 		let manifest_url = self.manifest_url.unwrap_or(b"some.url".to_vec());
 		let manifest_hash = self.manifest_hash.unwrap_or(b"0xdev".to_vec());
+		let manifest_hash_algorithm = self.manifest_hash_algorithm.unwrap_or(ManifestHashAlgorithm::Other);
 		let reputation_oracle = self.reputation_oracle.unwrap_or(3);
 		let recording_oracle = self.recording_oracle.unwrap_or(4);
 		let reputation_oracle_stake = self.reputation_oracle_stake.unwrap_or(Percent::from_percent(10));
 		let recording_oracle_stake = self.recording_oracle_stake.unwrap_or(Percent::from_percent(10));
+		let oracles = [
+			vec![
+				Oracle {
+					account: reputation_oracle,
+					role: OracleRole::Reputation,
+					fee: reputation_oracle_stake,
+					fixed_fee: 0,
+					fixed_fee_timing: FixedFeeTiming::Completion,
+				},
+				Oracle {
+					account: recording_oracle,
+					role: OracleRole::Recording,
+					fee: recording_oracle_stake,
+					fixed_fee: 0,
+					fixed_fee_timing: FixedFeeTiming::Completion,
+				},
+			],
+			self.other_oracles,
+		]
+		.concat();
 		let id = self.id.unwrap_or(0);
 		let account = Escrow::account_id_for(id);
 		let end_time = 1000;
@@ -89,50 +156,61 @@ impl EscrowBuilder {
 			canceller,
 			manifest_url,
 			manifest_hash,
-			reputation_oracle,
-			recording_oracle,
-			reputation_oracle_stake,
-			recording_oracle_stake,
+			manifest_hash_algorithm,
+			oracles,
+			upfront_fees_paid: false,
 			account,
 			factory,
+			oracle_keys: vec![],
+			terms: None,
+			paid_out: 0,
 		}
 	}
 }
 
-fn create_escrow(sender: AccountId, e: &EscrowInfo<Moment, AccountId>) -> DispatchResult {
+fn create_escrow(sender: AccountId, e: &EscrowInfo<Moment, AccountId, Balance>) -> DispatchResult {
 	let i = e.clone();
 	Escrow::create_factory(Origin::signed(sender))?;
 	Escrow::create(
 		Origin::signed(sender),
 		i.manifest_url,
 		i.manifest_hash,
+		i.manifest_hash_algorithm,
 		0,
-		i.reputation_oracle,
-		i.recording_oracle,
-		i.reputation_oracle_stake,
-		i.recording_oracle_stake,
+		i.oracles,
 	)
 }
 
-fn create_escrow_noop(sender: AccountId, e: &EscrowInfo<Moment, AccountId>) -> DispatchResult {
+fn create_escrow_noop(sender: AccountId, e: &EscrowInfo<Moment, AccountId, Balance>) -> DispatchResult {
 	let i = e.clone();
 	Escrow::create(
 		Origin::signed(sender),
 		i.manifest_url,
 		i.manifest_hash,
+		i.manifest_hash_algorithm,
+		0,
+		i.oracles,
+	)
+}
+
+fn create_escrow_with_terms(sender: AccountId, terms: JobTerms<Balance>) -> DispatchResult {
+	let i = EscrowBuilder::new().canceller(sender).build();
+	Escrow::create_from_terms(
+		Origin::signed(sender),
+		i.manifest_url,
+		i.manifest_hash,
+		i.manifest_hash_algorithm,
 		0,
-		i.reputation_oracle,
-		i.recording_oracle,
-		i.reputation_oracle_stake,
-		i.recording_oracle_stake,
+		i.oracles,
+		terms,
 	)
 }
 
-fn store_escrow(sender: AccountId, e: &EscrowInfo<Moment, AccountId>) {
+fn store_escrow(sender: AccountId, e: &EscrowInfo<Moment, AccountId, Balance>) {
 	assert_ok!(create_escrow(sender, e));
 }
 
-fn store_default_escrow(id: EscrowId, sender: AccountId) -> EscrowInfo<Moment, AccountId> {
+fn store_default_escrow(id: EscrowId, sender: AccountId) -> EscrowInfo<Moment, AccountId, Balance> {
 	let i = EscrowBuilder::new().id(id).canceller(sender).build();
 	store_escrow(sender, &i);
 	i
@@ -149,6 +227,16 @@ fn set_status(id: EscrowId, status: EscrowStatus) -> DispatchResult {
 	})
 }
 
+fn store_accepted_results(id: EscrowId, sender: AccountId) {
+	assert_ok!(Escrow::store_final_results(
+		Origin::signed(sender),
+		id,
+		b"results.url".to_vec(),
+		b"0xdev".to_vec()
+	));
+	assert_ok!(Escrow::accept_results(Origin::signed(sender), id));
+}
+
 fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
 	let events = frame_system::Module::<T>::events();
 	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
@@ -165,7 +253,7 @@ fn it_creates_escrow_instance() {
 		assert_eq!(Escrow::escrow(0), Some(escrow.clone()));
 		assert_eq!(Escrow::counter(), 1);
 		// Check that sender and oracles were set as trusted handlers.
-		let all_handlers = vec![escrow.reputation_oracle, escrow.recording_oracle, sender];
+		let all_handlers = vec![3, 4, sender];
 		for handler in all_handlers {
 			assert!(Escrow::is_trusted_handler(0, handler));
 		}
@@ -191,6 +279,22 @@ fn create_negative_tests() {
 				.build();
 			assert_noop!(create_escrow_noop(sender, &escrow), Error::<Test>::StakeOutOfBounds);
 		}
+		{
+			let escrow = EscrowBuilder::new()
+				.id(id)
+				.oracle(5, OracleRole::Exchange, Percent::from_percent(50))
+				.oracle(6, OracleRole::Other, Percent::from_percent(50))
+				.build();
+			assert_noop!(create_escrow_noop(sender, &escrow), Error::<Test>::StakeOutOfBounds);
+		}
+		{
+			let escrow = (0..(OraclesLimit::get() - 1) as u128)
+				.fold(EscrowBuilder::new().id(id), |builder, account| {
+					builder.oracle(10 + account, OracleRole::Other, Percent::from_percent(1))
+				})
+				.build();
+			assert_noop!(create_escrow_noop(sender, &escrow), Error::<Test>::TooManyOracles);
+		}
 		{
 			let escrow = EscrowBuilder::new().id(id).manifest_hash(vec![24; 101]).build();
 			assert_noop!(create_escrow_noop(sender, &escrow), Error::<Test>::StringSize);
@@ -267,6 +371,8 @@ fn abort_positive_tests() {
 		assert_eq!((balance_after - balance_before), 100);
 		assert!(!Escrow::is_trusted_handler(id, sender));
 		assert_eq!(Escrow::final_results(id), None);
+		assert_eq!(Escrow::results_review(id), None);
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::Aborted(id).into());
 	});
 }
 
@@ -295,6 +401,7 @@ fn cancel_positive_tests() {
 		assert_ok!(Balances::transfer(Origin::signed(1), escrow.account, 100));
 		assert_ok!(Escrow::cancel(Origin::signed(1), id));
 		assert_eq!(Escrow::escrow(id).unwrap().status, EscrowStatus::Cancelled);
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::Cancelled(id).into());
 	});
 }
 
@@ -320,9 +427,11 @@ fn complete_positive_tests() {
 	new_test_ext().execute_with(|| {
 		let sender = 1;
 		let _ = store_default_escrow(0, sender);
+		store_accepted_results(0, sender);
 		set_status(0, EscrowStatus::Paid).expect("setting status should work");
 		assert_ok!(Escrow::complete(Origin::signed(1), 0));
 		assert_eq!(Escrow::escrow(0).unwrap().status, EscrowStatus::Complete);
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::Completed(0).into());
 	});
 }
 
@@ -342,6 +451,11 @@ fn complete_negative_tests() {
 			Escrow::complete(Origin::signed(sender), 0),
 			Error::<Test>::EscrowNotPaid
 		);
+		set_status(0, EscrowStatus::Paid).expect("setting status should work");
+		assert_noop!(
+			Escrow::complete(Origin::signed(sender), 0),
+			Error::<Test>::ResultsNotAccepted
+		);
 		Timestamp::set_timestamp(1001);
 		assert_noop!(
 			Escrow::complete(Origin::signed(sender), 0),
@@ -364,7 +478,43 @@ fn store_results_positive_tests() {
 			url.clone(),
 			hash.clone()
 		));
-		assert_last_event::<Test>(RawEvent::<Test>::IntermediateResults(id, url, hash).into());
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::IntermediateResults(id, url.clone(), hash.clone()).into());
+		assert_eq!(
+			Escrow::intermediate_results(id),
+			vec![IntermediateResult {
+				block: 1,
+				url,
+				hash,
+				submitter: 1
+			}]
+		);
+	});
+}
+
+#[test]
+fn intermediate_results_history_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let limit = IntermediateResultsLimit::get() as u64;
+		for block in 1..=(limit + 1) {
+			System::set_block_number(block);
+			assert_ok!(Escrow::note_intermediate_results(
+				Origin::signed(sender),
+				id,
+				vec![block as u8],
+				b"0xdev".to_vec()
+			));
+		}
+		let history = Escrow::intermediate_results(id);
+		assert_eq!(history.len() as u64, limit);
+		// The oldest entry was dropped.
+		assert_eq!(history.first().map(|r| r.block), Some(2));
+		assert_eq!(history.last().map(|r| r.url.clone()), Some(vec![(limit + 1) as u8]));
+
+		assert_ok!(Escrow::abort(Origin::signed(sender), id));
+		assert!(Escrow::intermediate_results(id).is_empty());
 	});
 }
 
@@ -476,6 +626,311 @@ fn store_final_results_negative_tests() {
 	});
 }
 
+#[test]
+fn store_signed_final_results_works() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let relayer = 9;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		let signature = TestSignature(rec_oracle, (id, 0u32, &url, &hash).encode());
+		assert!(!Escrow::is_trusted_handler(id, relayer));
+		assert_ok!(Escrow::store_signed_final_results(
+			Origin::signed(relayer),
+			id,
+			url.clone(),
+			hash.clone(),
+			signature
+		));
+		assert_eq!(
+			Escrow::final_results(id),
+			Some(ResultInfo {
+				results_url: url,
+				results_hash: hash
+			})
+		);
+		assert_eq!(
+			Escrow::results_review(id),
+			Some(ResultsAcceptance::Pending(ResultsAcceptanceWindow::get()))
+		);
+		assert_eq!(Escrow::results_nonce(id), 1);
+	});
+}
+
+#[test]
+fn signed_final_results_cannot_be_replayed() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let relayer = 9;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		let old_signature = TestSignature(rec_oracle, (id, 0u32, &url, &hash).encode());
+		assert_ok!(Escrow::store_signed_final_results(
+			Origin::signed(relayer),
+			id,
+			url.clone(),
+			hash.clone(),
+			old_signature.clone()
+		));
+		// The recording oracle replaces the results.
+		let new_hash = b"0xnew".to_vec();
+		assert_ok!(Escrow::store_final_results(Origin::signed(rec_oracle), id, url.clone(), new_hash.clone()));
+		assert_eq!(Escrow::results_nonce(id), 2);
+		// The old attestation cannot restore the replaced results.
+		assert_noop!(
+			Escrow::store_signed_final_results(Origin::signed(relayer), id, url.clone(), hash.clone(), old_signature),
+			Error::<Test>::InvalidSignature
+		);
+		assert_eq!(
+			Escrow::final_results(id),
+			Some(ResultInfo {
+				results_url: url,
+				results_hash: new_hash
+			})
+		);
+	});
+}
+
+#[test]
+fn identical_final_results_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let relayer = 9;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		assert_ok!(Escrow::store_final_results(Origin::signed(sender), id, url.clone(), hash.clone()));
+		Timestamp::set_timestamp(50);
+		// Storing the same results again would extend the acceptance window.
+		assert_noop!(
+			Escrow::store_final_results(Origin::signed(sender), id, url.clone(), hash.clone()),
+			Error::<Test>::ResultsUnchanged
+		);
+		let signature = TestSignature(rec_oracle, (id, 1u32, &url, &hash).encode());
+		assert_noop!(
+			Escrow::store_signed_final_results(Origin::signed(relayer), id, url, hash, signature),
+			Error::<Test>::ResultsUnchanged
+		);
+		assert_eq!(
+			Escrow::results_review(id),
+			Some(ResultsAcceptance::Pending(ResultsAcceptanceWindow::get()))
+		);
+	});
+}
+
+#[test]
+fn expired_acceptance_window_is_not_reopened() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		assert_ok!(Escrow::store_final_results(Origin::signed(sender), id, url.clone(), b"0xdev".to_vec()));
+		Timestamp::set_timestamp(ResultsAcceptanceWindow::get());
+		assert!(Escrow::results_accepted(id));
+		assert_noop!(
+			Escrow::store_final_results(Origin::signed(sender), id, url, b"0xother".to_vec()),
+			Error::<Test>::ResultsAlreadyAccepted
+		);
+	});
+}
+
+#[test]
+fn store_signed_final_results_negative_tests() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rep_oracle = 3;
+		let rec_oracle = 4;
+		let relayer = 9;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		// Only the recording oracle can attest results.
+		let signature = TestSignature(rep_oracle, (id, 0u32, &url, &hash).encode());
+		assert_noop!(
+			Escrow::store_signed_final_results(Origin::signed(relayer), id, url.clone(), hash.clone(), signature),
+			Error::<Test>::InvalidSignature
+		);
+		// The signature has to cover the submitted results.
+		let signature = TestSignature(rec_oracle, (id, 0u32, &url, b"0xother".to_vec()).encode());
+		assert_noop!(
+			Escrow::store_signed_final_results(Origin::signed(relayer), id, url.clone(), hash.clone(), signature),
+			Error::<Test>::InvalidSignature
+		);
+		// The signature is bound to the escrow.
+		let signature = TestSignature(rec_oracle, (id + 1, 0u32, &url, &hash).encode());
+		assert_noop!(
+			Escrow::store_signed_final_results(Origin::signed(relayer), id, url.clone(), hash.clone(), signature),
+			Error::<Test>::InvalidSignature
+		);
+		let signature = TestSignature(rec_oracle, (id, 0u32, &url, &hash).encode());
+		set_status(id, EscrowStatus::Cancelled).expect("setting status should work");
+		assert_noop!(
+			Escrow::store_signed_final_results(Origin::signed(relayer), id, url, hash, signature),
+			Error::<Test>::EscrowClosed
+		);
+	});
+}
+
+#[test]
+fn commit_reveal_positive_tests() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		let salt = b"pepper".to_vec();
+		let commitment = Escrow::results_commitment_for(id, &rec_oracle, &url, &hash, &salt);
+		assert_ok!(Escrow::commit_results(Origin::signed(rec_oracle), id, commitment));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::ResultsCommitted(id, rec_oracle).into());
+		assert_eq!(Escrow::commitments_count(id), 1);
+		// No plaintext results while commitments are outstanding.
+		assert_noop!(
+			Escrow::store_final_results(Origin::signed(sender), id, url.clone(), hash.clone()),
+			Error::<Test>::CommitRevealActive
+		);
+		assert_ok!(Escrow::reveal_results(
+			Origin::signed(rec_oracle),
+			id,
+			url.clone(),
+			hash.clone(),
+			salt
+		));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::ResultsRevealed(id, rec_oracle).into());
+		assert_eq!(
+			Escrow::final_results(id),
+			Some(ResultInfo {
+				results_url: url,
+				results_hash: hash
+			})
+		);
+		assert_eq!(Escrow::results_commitment(id, rec_oracle), None);
+		assert_eq!(Escrow::commitments_count(id), 0);
+		assert_eq!(
+			Escrow::results_review(id),
+			Some(ResultsAcceptance::Pending(ResultsAcceptanceWindow::get()))
+		);
+		// Once results are public, committing to them would allow copying.
+		assert_noop!(
+			Escrow::commit_results(Origin::signed(3), id, commitment),
+			Error::<Test>::ResultsAlreadyPublished
+		);
+	});
+}
+
+#[test]
+fn copied_commitment_cannot_be_revealed() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		let salt = b"pepper".to_vec();
+		let commitment = Escrow::results_commitment_for(id, &rec_oracle, &url, &hash, &salt);
+		assert_ok!(Escrow::commit_results(Origin::signed(rec_oracle), id, commitment));
+		// Another trusted handler copies the commitment seen on-chain.
+		assert_ok!(Escrow::commit_results(Origin::signed(sender), id, commitment));
+		assert_noop!(
+			Escrow::reveal_results(Origin::signed(sender), id, url.clone(), hash.clone(), salt.clone()),
+			Error::<Test>::CommitmentMismatch
+		);
+		assert_ok!(Escrow::reveal_results(Origin::signed(rec_oracle), id, url.clone(), hash.clone(), salt.clone()));
+		// The copy is dropped and results cannot be revealed again.
+		assert_eq!(Escrow::results_commitment(id, sender), None);
+		assert_eq!(Escrow::commitments_count(id), 0);
+		let commitment = Escrow::results_commitment_for(id, &sender, &url, &hash, &salt);
+		assert_noop!(
+			Escrow::reveal_results(Origin::signed(sender), id, url, hash, salt),
+			Error::<Test>::ResultsAlreadyPublished
+		);
+		assert_noop!(
+			Escrow::commit_results(Origin::signed(sender), id, commitment),
+			Error::<Test>::ResultsAlreadyPublished
+		);
+	});
+}
+
+#[test]
+fn commit_reveal_negative_tests() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		let salt = b"pepper".to_vec();
+		let commitment = Escrow::results_commitment_for(id, &rec_oracle, &url, &hash, &salt);
+		assert_noop!(
+			Escrow::commit_results(Origin::signed(8), id, commitment),
+			Error::<Test>::NonTrustedAccount
+		);
+		assert_noop!(
+			Escrow::reveal_results(Origin::signed(rec_oracle), id, url.clone(), hash.clone(), salt.clone()),
+			Error::<Test>::MissingCommitment
+		);
+		assert_ok!(Escrow::commit_results(Origin::signed(rec_oracle), id, commitment));
+		assert_noop!(
+			Escrow::commit_results(Origin::signed(rec_oracle), id, commitment),
+			Error::<Test>::AlreadyCommitted
+		);
+		assert_noop!(
+			Escrow::reveal_results(Origin::signed(rec_oracle), id, url.clone(), hash.clone(), b"salt".to_vec()),
+			Error::<Test>::CommitmentMismatch
+		);
+		assert_noop!(
+			Escrow::flag_unrevealed(Origin::signed(8), id, rec_oracle),
+			Error::<Test>::RevealWindowOpen
+		);
+		Timestamp::set_timestamp(RevealWindow::get() + 1);
+		assert_noop!(
+			Escrow::reveal_results(Origin::signed(rec_oracle), id, url, hash, salt),
+			Error::<Test>::RevealWindowClosed
+		);
+	});
+}
+
+#[test]
+fn flag_unrevealed_works() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let commitment = BlakeTwo256::hash(b"0xdevpepper");
+		assert_ok!(Escrow::commit_results(Origin::signed(rec_oracle), id, commitment));
+		Timestamp::set_timestamp(RevealWindow::get() + 1);
+		assert_ok!(Escrow::flag_unrevealed(Origin::signed(8), id, rec_oracle));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::CommitmentUnrevealed(id, rec_oracle).into());
+		assert_eq!(Escrow::missed_reveals(rec_oracle), 1);
+		assert_eq!(Escrow::commitments_count(id), 0);
+		assert_noop!(
+			Escrow::flag_unrevealed(Origin::signed(8), id, rec_oracle),
+			Error::<Test>::MissingCommitment
+		);
+		// Plaintext results are possible again.
+		assert_ok!(Escrow::store_final_results(
+			Origin::signed(sender),
+			id,
+			b"results.url".to_vec(),
+			b"0xdev".to_vec()
+		));
+	});
+}
+
 #[test]
 fn bulk_payout_positive_tests() {
 	new_test_ext().execute_with(|| {
@@ -501,54 +956,351 @@ fn bulk_payout_positive_tests() {
 			recipients.clone(),
 			amounts.clone(),
 		));
-		assert_last_event::<Test>(RawEvent::<Test>::BulkPayout(id).into());
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::BulkPayout(id, vec![(rep_oracle, 2), (rec_oracle, 2)], 0).into());
 		assert_eq!(Balances::free_balance(rep_oracle), 2);
 		assert_eq!(Balances::free_balance(rec_oracle), 2);
 		assert_eq!(Balances::free_balance(recipients[0]), 8);
 		assert_eq!(Balances::free_balance(recipients[1]), 8);
 
 		assert_eq!(Escrow::escrow(0).unwrap().status, EscrowStatus::Partial);
+		store_accepted_results(id, sender);
 		assert_ok!(Escrow::bulk_payout(Origin::signed(1), id, recipients.clone(), amounts,));
 		assert_eq!(Escrow::escrow(0).unwrap().status, EscrowStatus::Paid);
 	});
 }
 
 #[test]
-fn bulk_payout_negative_tests() {
+fn bulk_payout_multiple_oracles() {
 	new_test_ext().execute_with(|| {
 		let sender = 1;
-		let rep_oracle = 3;
-		let rec_oracle = 4;
-		let mut recipients = vec![5, 6];
-		let amounts = vec![10, 10];
+		let exchange_oracle = 7;
+		let vendor = 8;
+		let recipients = vec![5, 6];
+		let amounts = vec![100, 100];
 		let id = 0;
 		let escrow = EscrowBuilder::new()
 			.id(id)
 			.canceller(sender)
-			.reputation_oracle(rep_oracle)
 			.reputation_stake(Percent::from_percent(10))
-			.recording_oracle(rec_oracle)
-			.recording_stake(Percent::from_percent(10))
+			.recording_stake(Percent::from_percent(5))
+			.oracle(exchange_oracle, OracleRole::Exchange, Percent::from_percent(3))
+			.oracle(vendor, OracleRole::Other, Percent::from_percent(2))
 			.build();
 		store_escrow(sender, &escrow);
-		// Set the trusted handler manually to trigger missing escrow error.
-		TrustedHandlers::<Test>::insert(2, sender, true);
-		assert_noop!(
-			Escrow::bulk_payout(Origin::signed(1), 2, recipients.clone(), amounts.clone(),),
-			Error::<Test>::MissingEscrow
-		);
-		assert_noop!(
-			Escrow::bulk_payout(Origin::signed(9), id, recipients.clone(), amounts.clone(),),
-			Error::<Test>::NonTrustedAccount
+		assert_eq!(Escrow::oracles(id), escrow.oracles);
+		assert!(Escrow::is_trusted_handler(id, exchange_oracle));
+		assert!(Escrow::is_trusted_handler(id, vendor));
+		assert_ok!(Balances::transfer(Origin::signed(1), escrow.account, 300));
+		assert_ok!(Escrow::bulk_payout(Origin::signed(1), id, recipients.clone(), amounts));
+		assert_last_event::<Test>(
+			RawEvent::<AccountId, Balance>::BulkPayout(id, vec![(3, 20), (4, 10), (exchange_oracle, 6), (vendor, 4)], 0).into(),
 		);
+		assert_eq!(Balances::free_balance(3), 20);
+		assert_eq!(Balances::free_balance(4), 10);
+		assert_eq!(Balances::free_balance(exchange_oracle), 6);
+		assert_eq!(Balances::free_balance(vendor), 4);
+		assert_eq!(Balances::free_balance(recipients[0]), 80);
+		assert_eq!(Balances::free_balance(recipients[1]), 80);
+	});
+}
+
+#[test]
+fn fixed_fees_positive() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let exchange_oracle = 7;
+		let vendor = 8;
+		let recipient = 5;
+		let id = 0;
+		let escrow = EscrowBuilder::new()
+			.id(id)
+			.canceller(sender)
+			.oracle_with_fixed_fee(exchange_oracle, OracleRole::Exchange, Percent::from_percent(0), 50, FixedFeeTiming::Upfront)
+			.oracle_with_fixed_fee(vendor, OracleRole::Other, Percent::from_percent(5), 30, FixedFeeTiming::Completion)
+			.build();
+		store_escrow(sender, &escrow);
+		// Not enough funds for the upfront fees yet.
+		assert_ok!(Escrow::fund(Origin::signed(sender), id, 20));
+		assert_eq!(Balances::free_balance(exchange_oracle), 0);
+		assert_ok!(Escrow::fund(Origin::signed(sender), id, 180));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::FixedFeesPaid(id, vec![(exchange_oracle, 50)]).into());
+		assert_eq!(Balances::free_balance(exchange_oracle), 50);
+		// The upfront fees are only paid once.
+		assert_ok!(Escrow::fund(Origin::signed(sender), id, 50));
+		assert_eq!(Balances::free_balance(exchange_oracle), 50);
+		// The completion fee is held back.
+		let escrow = Escrow::escrow(id).unwrap();
+		assert_eq!(Escrow::payable_balance(&escrow), 170);
 		assert_noop!(
-			Escrow::bulk_payout(Origin::signed(1), id, recipients.clone(), amounts.clone(),),
+			Escrow::bulk_payout(Origin::signed(sender), id, vec![recipient], vec![180]),
 			Error::<Test>::OutOfFunds
 		);
-		assert_ok!(Balances::transfer(Origin::signed(1), escrow.account, 10));
-		assert_noop!(
-			Escrow::bulk_payout(Origin::signed(1), id, recipients.clone(), amounts.clone(),),
-			Error::<Test>::OutOfFunds
+		store_accepted_results(id, sender);
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, vec![recipient], vec![170]));
+		assert_eq!(Escrow::escrow(id).unwrap().status, EscrowStatus::Paid);
+		assert_eq!(Balances::free_balance(vendor), 8);
+		assert_eq!(Balances::free_balance(recipient), 170 - 17 - 17 - 8);
+		assert_eq!(Balances::free_balance(escrow.account), 30);
+		assert_ok!(Escrow::complete(Origin::signed(sender), id));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::FixedFeesPaid(id, vec![(vendor, 30)]).into());
+		assert_eq!(Balances::free_balance(vendor), 38);
+		assert_eq!(Balances::free_balance(escrow.account), 0);
+	});
+}
+
+#[test]
+fn fixed_fees_paid_at_payout_and_refunded_on_cancel() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let exchange_oracle = 7;
+		let vendor = 8;
+		let recipient = 5;
+		let id = 0;
+		let escrow = EscrowBuilder::new()
+			.id(id)
+			.canceller(sender)
+			.reputation_stake(Percent::from_percent(0))
+			.recording_stake(Percent::from_percent(0))
+			.oracle_with_fixed_fee(exchange_oracle, OracleRole::Exchange, Percent::from_percent(0), 50, FixedFeeTiming::Upfront)
+			.oracle_with_fixed_fee(vendor, OracleRole::Other, Percent::from_percent(0), 30, FixedFeeTiming::Completion)
+			.build();
+		store_escrow(sender, &escrow);
+		// Funding by plain transfer leaves the upfront fees to the first payout.
+		assert_ok!(Balances::transfer(Origin::signed(sender), escrow.account, 40));
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(sender), id, vec![recipient], vec![10]),
+			Error::<Test>::OutOfFunds
+		);
+		assert_ok!(Balances::transfer(Origin::signed(sender), escrow.account, 160));
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, vec![recipient], vec![10]));
+		assert_eq!(Balances::free_balance(exchange_oracle), 50);
+		assert_eq!(Balances::free_balance(recipient), 10);
+		assert_last_event::<Test>(
+			RawEvent::<AccountId, Balance>::BulkPayout(id, vec![(3, 0), (4, 0), (exchange_oracle, 0), (vendor, 0)], 0).into(),
+		);
+		// Paid upfront fees are kept, the unpaid completion fee is refunded.
+		assert_ok!(Escrow::cancel(Origin::signed(sender), id));
+		assert_eq!(Balances::free_balance(escrow.account), 0);
+		assert_eq!(Balances::free_balance(vendor), 0);
+		assert_eq!(Balances::free_balance(sender), 1_000 - 50 - 10);
+	});
+}
+
+#[test]
+fn bulk_payout_protocol_fee() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let recipients = vec![5, 6];
+		let amounts = vec![100, 100];
+		let id = 0;
+		let escrow = store_default_escrow(id, sender);
+		assert_noop!(
+			Escrow::set_protocol_fee(Origin::signed(sender), Percent::from_percent(5)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Escrow::set_protocol_fee(Origin::root(), Percent::from_percent(5)));
+		assert_eq!(Escrow::protocol_fee(), Percent::from_percent(5));
+		assert_ok!(Balances::transfer(Origin::signed(sender), escrow.account, 300));
+		assert_eq!(
+			Escrow::preview_payout(id, amounts.clone()),
+			Some(PayoutPreview {
+				oracle_fees: vec![(3, 20), (4, 20)],
+				protocol_fee: 10,
+				amounts: vec![75, 75],
+			})
+		);
+		let issuance = Balances::total_issuance();
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, recipients.clone(), amounts));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::BulkPayout(id, vec![(3, 20), (4, 20)], 10).into());
+		assert_eq!(Balances::free_balance(recipients[0]), 75);
+		assert_eq!(Balances::free_balance(recipients[1]), 75);
+		assert_eq!(Balances::free_balance(escrow.account), 100);
+		// The mock drops the protocol fee.
+		assert_eq!(Balances::total_issuance(), issuance - 10);
+		assert_eq!(Escrow::preview_payout(1, vec![10]), None);
+	});
+}
+
+#[test]
+fn final_payout_requires_accepted_results() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let escrow = store_default_escrow(id, sender);
+		assert_ok!(Balances::transfer(Origin::signed(1), escrow.account, 20));
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(1), id, vec![5, 6], vec![10, 10]),
+			Error::<Test>::ResultsNotAccepted
+		);
+		assert_ok!(Escrow::store_final_results(
+			Origin::signed(sender),
+			id,
+			b"results.url".to_vec(),
+			b"0xdev".to_vec()
+		));
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(1), id, vec![5, 6], vec![10, 10]),
+			Error::<Test>::ResultsNotAccepted
+		);
+		// Partial payouts are not affected.
+		assert_ok!(Escrow::bulk_payout(Origin::signed(1), id, vec![5], vec![10]));
+		// The results count as accepted once the window has passed.
+		Timestamp::set_timestamp(ResultsAcceptanceWindow::get());
+		assert_ok!(Escrow::bulk_payout(Origin::signed(1), id, vec![6], vec![10]));
+		assert_eq!(Escrow::escrow(id).unwrap().status, EscrowStatus::Paid);
+	});
+}
+
+#[test]
+fn accept_results_positive_tests() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		assert!(!Escrow::results_accepted(id));
+		store_accepted_results(id, sender);
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::ResultsAccepted(id).into());
+		assert_eq!(Escrow::results_review(id), Some(ResultsAcceptance::Accepted));
+		assert!(Escrow::results_accepted(id));
+	});
+}
+
+#[test]
+fn accept_results_negative_tests() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		assert_noop!(Escrow::accept_results(Origin::signed(sender), 2), Error::<Test>::MissingEscrow);
+		assert_noop!(Escrow::accept_results(Origin::signed(sender), id), Error::<Test>::MissingResults);
+		assert_ok!(Escrow::store_final_results(
+			Origin::signed(sender),
+			id,
+			b"results.url".to_vec(),
+			b"0xdev".to_vec()
+		));
+		// Oracles are trusted handlers but not the requester.
+		assert_noop!(Escrow::accept_results(Origin::signed(3), id), Error::<Test>::NotRequester);
+		assert_ok!(Escrow::accept_results(Origin::signed(sender), id));
+		assert_noop!(
+			Escrow::store_final_results(Origin::signed(sender), id, b"other.url".to_vec(), b"0xdev".to_vec()),
+			Error::<Test>::ResultsAlreadyAccepted
+		);
+		set_status(id, EscrowStatus::Cancelled).expect("setting status should work");
+		assert_noop!(Escrow::accept_results(Origin::signed(sender), id), Error::<Test>::EscrowClosed);
+	});
+}
+
+#[test]
+fn reject_results_opens_dispute() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		assert_noop!(Escrow::reject_results(Origin::signed(sender), id), Error::<Test>::MissingResults);
+		assert_ok!(Escrow::store_final_results(
+			Origin::signed(sender),
+			id,
+			b"results.url".to_vec(),
+			b"0xdev".to_vec()
+		));
+		assert_noop!(Escrow::reject_results(Origin::signed(4), id), Error::<Test>::NotRequester);
+		assert_ok!(Escrow::reject_results(Origin::signed(sender), id));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::ResultsRejected(id).into());
+		assert_eq!(Escrow::results_review(id), Some(ResultsAcceptance::Rejected));
+		// A rejection does not expire with the window.
+		Timestamp::set_timestamp(ResultsAcceptanceWindow::get());
+		assert!(!Escrow::results_accepted(id));
+		// New results reopen the acceptance window.
+		assert_ok!(Escrow::store_final_results(
+			Origin::signed(3),
+			id,
+			b"fixed.url".to_vec(),
+			b"0xdev".to_vec()
+		));
+		assert_eq!(
+			Escrow::results_review(id),
+			Some(ResultsAcceptance::Pending(2 * ResultsAcceptanceWindow::get()))
+		);
+		Timestamp::set_timestamp(2 * ResultsAcceptanceWindow::get());
+		assert_noop!(
+			Escrow::reject_results(Origin::signed(sender), id),
+			Error::<Test>::AcceptanceWindowClosed
+		);
+		assert!(Escrow::results_accepted(id));
+	});
+}
+
+#[test]
+fn rejected_results_can_be_committed_to_again() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rec_oracle = 4;
+		let id = 0;
+		let _ = store_default_escrow(id, sender);
+		let url = b"results.url".to_vec();
+		let hash = b"0xdev".to_vec();
+		let salt = b"pepper".to_vec();
+		let commitment = Escrow::results_commitment_for(id, &rec_oracle, &url, &hash, &salt);
+		assert_ok!(Escrow::commit_results(Origin::signed(rec_oracle), id, commitment));
+		assert_ok!(Escrow::reveal_results(Origin::signed(rec_oracle), id, url, hash, salt));
+		assert_ok!(Escrow::reject_results(Origin::signed(sender), id));
+
+		let url = b"fixed.url".to_vec();
+		let hash = b"0xfixed".to_vec();
+		let salt = b"salt".to_vec();
+		let commitment = Escrow::results_commitment_for(id, &rec_oracle, &url, &hash, &salt);
+		assert_ok!(Escrow::commit_results(Origin::signed(rec_oracle), id, commitment));
+		assert_ok!(Escrow::reveal_results(Origin::signed(rec_oracle), id, url.clone(), hash.clone(), salt));
+		assert_eq!(Escrow::final_results(id), Some(ResultInfo { results_url: url, results_hash: hash }));
+		assert_eq!(
+			Escrow::results_review(id),
+			Some(ResultsAcceptance::Pending(ResultsAcceptanceWindow::get()))
+		);
+		// The new results are not open to commitments unless rejected again.
+		assert_noop!(
+			Escrow::commit_results(Origin::signed(rec_oracle), id, commitment),
+			Error::<Test>::ResultsAlreadyPublished
+		);
+	});
+}
+
+#[test]
+fn bulk_payout_negative_tests() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let rep_oracle = 3;
+		let rec_oracle = 4;
+		let mut recipients = vec![5, 6];
+		let amounts = vec![10, 10];
+		let id = 0;
+		let escrow = EscrowBuilder::new()
+			.id(id)
+			.canceller(sender)
+			.reputation_oracle(rep_oracle)
+			.reputation_stake(Percent::from_percent(10))
+			.recording_oracle(rec_oracle)
+			.recording_stake(Percent::from_percent(10))
+			.build();
+		store_escrow(sender, &escrow);
+		// Set the trusted handler manually to trigger missing escrow error.
+		TrustedHandlers::<Test>::insert(2, sender, true);
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(1), 2, recipients.clone(), amounts.clone(),),
+			Error::<Test>::MissingEscrow
+		);
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(9), id, recipients.clone(), amounts.clone(),),
+			Error::<Test>::NonTrustedAccount
+		);
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(1), id, recipients.clone(), amounts.clone(),),
+			Error::<Test>::OutOfFunds
+		);
+		assert_ok!(Balances::transfer(Origin::signed(1), escrow.account, 10));
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(1), id, recipients.clone(), amounts.clone(),),
+			Error::<Test>::OutOfFunds
 		);
 		recipients.push(7);
 		assert_ok!(Balances::transfer(Origin::signed(1), escrow.account, 20));
@@ -580,9 +1332,9 @@ fn finalize_payouts_simple_case() {
 		.recording_stake(Percent::from_percent(10))
 		.build();
 	let amounts = vec![10, 10];
-	let (reputation_fee, recording_fee, final_amounts) = Escrow::finalize_payouts(&escrow, &amounts);
-	assert_eq!(reputation_fee, 2);
-	assert_eq!(recording_fee, 2);
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(0), &amounts);
+	assert_eq!(protocol_fee, 0);
+	assert_eq!(fees, vec![2, 2]);
 	assert_eq!(final_amounts, vec![8, 8]);
 }
 
@@ -593,9 +1345,9 @@ fn finalize_payouts_rounds_down() {
 		.recording_stake(Percent::from_percent(1))
 		.build();
 	let amounts = vec![50];
-	let (reputation_fee, recording_fee, final_amounts) = Escrow::finalize_payouts(&escrow, &amounts);
-	assert_eq!(reputation_fee, 1);
-	assert_eq!(recording_fee, 0);
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(0), &amounts);
+	assert_eq!(protocol_fee, 0);
+	assert_eq!(fees, vec![1, 0]);
 	assert_eq!(final_amounts, vec![49]);
 }
 
@@ -607,10 +1359,10 @@ fn finalize_payouts_big_numbers() {
 		.build();
 	let big_amount = 1_000_000_000_000_000;
 	let amounts = vec![big_amount, big_amount, big_amount];
-	let (reputation_fee, recording_fee, final_amounts) = Escrow::finalize_payouts(&escrow, &amounts);
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(0), &amounts);
+	assert_eq!(protocol_fee, 0);
 	let final_payout = big_amount - big_amount / 4 - big_amount / 5;
-	assert_eq!(reputation_fee, 3 * big_amount / 4);
-	assert_eq!(recording_fee, 3 * big_amount / 5);
+	assert_eq!(fees, vec![3 * big_amount / 4, 3 * big_amount / 5]);
 	assert_eq!(final_amounts, vec![final_payout, final_payout, final_payout]);
 }
 
@@ -622,9 +1374,46 @@ fn finalize_payouts_high_fees() {
 		.build();
 	let big_amount = 1_000_000_000_000_000;
 	let amounts = vec![big_amount];
-	let (reputation_fee, recording_fee, final_amounts) = Escrow::finalize_payouts(&escrow, &amounts);
-	assert_eq!(reputation_fee, big_amount / 2);
-	assert_eq!(recording_fee, big_amount / 2);
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(0), &amounts);
+	assert_eq!(protocol_fee, 0);
+	assert_eq!(fees, vec![big_amount / 2, big_amount / 2]);
+	assert_eq!(final_amounts, vec![0]);
+}
+
+#[test]
+fn finalize_payouts_without_oracles() {
+	let mut escrow = EscrowBuilder::new().build();
+	escrow.oracles.clear();
+	let amounts = vec![10, 20];
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(0), &amounts);
+	assert_eq!(protocol_fee, 0);
+	assert!(fees.is_empty());
+	assert_eq!(final_amounts, amounts);
+}
+
+#[test]
+fn finalize_payouts_protocol_fee() {
+	let escrow = EscrowBuilder::new()
+		.reputation_stake(Percent::from_percent(10))
+		.recording_stake(Percent::from_percent(10))
+		.build();
+	let amounts = vec![100, 50];
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(5), &amounts);
+	assert_eq!(fees, vec![15, 15]);
+	assert_eq!(protocol_fee, 7);
+	assert_eq!(final_amounts, vec![75, 38]);
+}
+
+#[test]
+fn finalize_payouts_protocol_fee_is_capped() {
+	let escrow = EscrowBuilder::new()
+		.reputation_stake(Percent::from_percent(50))
+		.recording_stake(Percent::from_percent(40))
+		.build();
+	let amounts = vec![100];
+	let (fees, protocol_fee, final_amounts) = Escrow::finalize_payouts(&escrow, Percent::from_percent(20), &amounts);
+	assert_eq!(fees, vec![50, 40]);
+	assert_eq!(protocol_fee, 10);
 	assert_eq!(final_amounts, vec![0]);
 }
 
@@ -674,3 +1463,477 @@ fn bulk_transfer_fails() {
 		);
 	});
 }
+
+#[test]
+fn events_and_escrows_for_subscriptions() {
+	new_test_ext().execute_with(|| {
+		let escrow = store_default_escrow(0, 1);
+		assert_eq!(escrow.status(), EscrowStatus::Pending);
+		for who in &[escrow.account, 1, 3, 4] {
+			assert!(escrow.involves(who));
+		}
+		assert!(!escrow.involves(&8));
+
+		assert_eq!(RawEvent::<AccountId, Balance>::Cancelled(2).escrow_id(), Some(2));
+		assert_eq!(RawEvent::<AccountId, Balance>::BulkPayout(3, vec![], 0).escrow_id(), Some(3));
+		assert_eq!(RawEvent::<AccountId, Balance>::FactoryCreated(0, 1).escrow_id(), None);
+	});
+}
+
+#[test]
+fn escrows_are_indexed_by_party() {
+	new_test_ext().execute_with(|| {
+		let launcher = 1;
+		let rep_oracle = 3;
+		let rec_oracle = 4;
+		for id in 0..3 {
+			store_default_escrow(id, launcher);
+		}
+		let rep = EscrowParty::Oracle(OracleRole::Reputation);
+		let rec = EscrowParty::Oracle(OracleRole::Recording);
+		assert_eq!(Escrow::escrows_of(launcher, EscrowParty::Launcher, None, 10), vec![0, 1, 2]);
+		assert_eq!(Escrow::escrows_of(rep_oracle, rep, None, 10), vec![0, 1, 2]);
+		assert_eq!(Escrow::escrows_of(rec_oracle, rec, None, 10), vec![0, 1, 2]);
+		// The index is by party, not only by account.
+		assert!(Escrow::escrows_of(rec_oracle, rep, None, 10).is_empty());
+		assert!(Escrow::escrows_of(launcher, rec, None, 10).is_empty());
+
+		// Pages start after the last id of the previous one.
+		assert_eq!(Escrow::escrows_of(rec_oracle, rec, None, 2), vec![0, 1]);
+		assert_eq!(Escrow::escrows_of(rec_oracle, rec, Some(1), 2), vec![2]);
+		assert!(Escrow::escrows_of(rec_oracle, rec, Some(2), 2).is_empty());
+
+		assert_ok!(Escrow::abort(Origin::signed(launcher), 1));
+		assert_eq!(Escrow::escrows_of(launcher, EscrowParty::Launcher, None, 10), vec![0, 2]);
+		assert_eq!(Escrow::escrows_of(rep_oracle, rep, None, 10), vec![0, 2]);
+		assert_eq!(Escrow::escrows_of(rec_oracle, rec, None, 10), vec![0, 2]);
+	});
+}
+
+#[test]
+fn escrow_index_pages_follow_ids() {
+	new_test_ext().execute_with(|| {
+		let launcher = 1;
+		let ids = vec![1, 255, 256, 65_536, 1 << 64];
+		for id in ids.iter().rev() {
+			Counter::put(*id);
+			store_default_escrow(*id, launcher);
+		}
+		// Another party's escrows are not part of the pages.
+		Counter::put(2);
+		store_default_escrow(2, 7);
+		assert_eq!(Escrow::escrows_of(7, EscrowParty::Launcher, None, 10), vec![2]);
+		let party = EscrowParty::Launcher;
+		assert_eq!(Escrow::escrows_of(launcher, party, None, 10), ids);
+		assert_eq!(Escrow::escrows_of(launcher, party, None, 2), vec![1, 255]);
+		assert_eq!(Escrow::escrows_of(launcher, party, Some(255), 2), vec![256, 65_536]);
+		assert_eq!(Escrow::escrows_of(launcher, party, Some(65_536), 2), vec![1 << 64]);
+		// Pages can start after ids that are not indexed.
+		assert_eq!(Escrow::escrows_of(launcher, party, Some(300), 1), vec![65_536]);
+		assert!(Escrow::escrows_of(launcher, party, Some(1 << 64), 2).is_empty());
+		assert!(Escrow::escrows_of(launcher, party, None, 0).is_empty());
+	});
+}
+
+#[test]
+fn oracle_keys_are_recorded_at_creation() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		publish_key(3, b"reputation key".to_vec());
+		publish_key(5, b"exchange key".to_vec());
+		let e = EscrowBuilder::new()
+			.canceller(sender)
+			.oracle(5, OracleRole::Exchange, Percent::from_percent(10))
+			.build();
+		store_escrow(sender, &e);
+		let expected = vec![(3, b"reputation key".to_vec()), (5, b"exchange key".to_vec())];
+		assert_eq!(Escrow::oracle_keys(0), expected);
+
+		// Rotating a key does not change the snapshot.
+		publish_key(3, b"rotated key".to_vec());
+		assert_eq!(Escrow::escrow(0).unwrap().oracle_keys(), &expected[..]);
+	});
+}
+
+#[test]
+fn oracle_keys_can_be_required() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		set_require_oracle_keys(true);
+		// Oracles without encryption duties do not need a key.
+		let e = EscrowBuilder::new()
+			.canceller(sender)
+			.oracle(5, OracleRole::Exchange, Percent::from_percent(10))
+			.build();
+		assert_ok!(Escrow::create_factory(Origin::signed(sender)));
+		assert_noop!(create_escrow_noop(sender, &e), Error::<Test>::MissingOracleKey);
+		publish_key(3, b"reputation key".to_vec());
+		assert_noop!(create_escrow_noop(sender, &e), Error::<Test>::MissingOracleKey);
+		publish_key(4, b"recording key".to_vec());
+		assert_ok!(create_escrow_noop(sender, &e));
+		assert_eq!(Escrow::oracle_keys(0), vec![(3, b"reputation key".to_vec()), (4, b"recording key".to_vec())]);
+	});
+}
+
+#[test]
+fn create_from_terms_works() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let terms = JobTerms { total_tasks: 3, price_per_task: 10, min_repeats: 1, max_repeats: 2 };
+		assert_ok!(Escrow::create_factory(Origin::signed(sender)));
+		let invalid = |change: fn(&mut JobTerms<Balance>)| {
+			let mut terms = terms.clone();
+			change(&mut terms);
+			terms
+		};
+		let invalid_terms = vec![
+			invalid(|t| t.total_tasks = 0),
+			invalid(|t| t.price_per_task = 0),
+			invalid(|t| t.min_repeats = 0),
+			invalid(|t| t.min_repeats = 3),
+			invalid(|t| t.price_per_task = Balance::max_value()),
+		];
+		for terms in invalid_terms {
+			assert_noop!(create_escrow_with_terms(sender, terms), Error::<Test>::InvalidTerms);
+		}
+
+		assert_ok!(create_escrow_with_terms(sender, terms.clone()));
+		let escrow = Escrow::escrow(0).unwrap();
+		assert_eq!(escrow.terms(), Some(&terms));
+		assert_eq!(escrow.paid_out(), &0);
+		assert_last_event::<Test>(
+			RawEvent::Pending(0, sender, escrow.manifest_url, escrow.manifest_hash, escrow.account, escrow.oracles).into()
+		);
+		// Escrows created without terms have no budget.
+		store_default_escrow(1, sender);
+		assert_eq!(Escrow::escrow(1).unwrap().terms(), None);
+	});
+}
+
+#[test]
+fn bulk_payout_is_bounded_by_terms() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let terms = JobTerms { total_tasks: 3, price_per_task: 10, min_repeats: 1, max_repeats: 2 };
+		assert_ok!(Escrow::create_factory(Origin::signed(sender)));
+		assert_ok!(create_escrow_with_terms(sender, terms));
+		let account = Escrow::escrow(id).unwrap().account;
+		// The escrow holds more than the budget of 30.
+		assert_ok!(Balances::transfer(Origin::signed(1), account, 100));
+
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, vec![5, 6], vec![10, 10]));
+		assert_eq!(Escrow::escrow(id).unwrap().paid_out(), &20);
+		assert_eq!(Escrow::escrow(id).unwrap().status, EscrowStatus::Partial);
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(sender), id, vec![5], vec![11]),
+			Error::<Test>::BudgetExceeded
+		);
+		// Spending the budget requires accepted results like spending the balance.
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(sender), id, vec![5], vec![10]),
+			Error::<Test>::ResultsNotAccepted
+		);
+		store_accepted_results(id, sender);
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, vec![5], vec![10]));
+		let escrow = Escrow::escrow(id).unwrap();
+		assert_eq!(escrow.paid_out(), &30);
+		assert_eq!(escrow.status, EscrowStatus::Paid);
+		// The balance above the budget was refunded.
+		assert_eq!(Balances::free_balance(account), 0);
+		assert_noop!(
+			Escrow::bulk_payout(Origin::signed(sender), id, vec![5], vec![1]),
+			Error::<Test>::EscrowClosed
+		);
+	});
+}
+
+#[test]
+fn funding_is_bounded_by_terms() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let terms = JobTerms { total_tasks: 3, price_per_task: 10, min_repeats: 1, max_repeats: 2 };
+		assert_ok!(Escrow::create_factory(Origin::signed(sender)));
+		assert_ok!(create_escrow_with_terms(sender, terms));
+		assert_noop!(Escrow::fund(Origin::signed(sender), id, 31), Error::<Test>::FundingExceedsBudget);
+		assert_ok!(Escrow::fund(Origin::signed(sender), id, 20));
+		assert_noop!(Escrow::fund(Origin::signed(sender), id, 11), Error::<Test>::FundingExceedsBudget);
+		assert_ok!(Escrow::fund(Origin::signed(sender), id, 10));
+		// Funds paid out no longer count towards the budget.
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, vec![5], vec![10]));
+		assert_noop!(Escrow::fund(Origin::signed(sender), id, 1), Error::<Test>::FundingExceedsBudget);
+
+		// Escrows without terms can be funded with any amount.
+		let e = EscrowBuilder::new().canceller(sender).build();
+		assert_ok!(create_escrow_noop(sender, &e));
+		assert_ok!(Escrow::fund(Origin::signed(sender), 1, 100));
+	});
+}
+
+#[test]
+fn overfunded_escrow_refunds_excess() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let id = 0;
+		let terms = JobTerms { total_tasks: 2, price_per_task: 10, min_repeats: 1, max_repeats: 1 };
+		assert_ok!(Escrow::create_factory(Origin::signed(sender)));
+		assert_ok!(create_escrow_with_terms(sender, terms));
+		let account = Escrow::escrow(id).unwrap().account;
+		// Plain transfers are not bounded by the budget.
+		assert_ok!(Balances::transfer(Origin::signed(sender), account, 50));
+		store_accepted_results(id, sender);
+		let before = Balances::free_balance(sender);
+
+		assert_ok!(Escrow::bulk_payout(Origin::signed(sender), id, vec![5, 6], vec![10, 10]));
+		assert_eq!(Escrow::escrow(id).unwrap().status, EscrowStatus::Paid);
+		assert_eq!(Balances::free_balance(account), 0);
+		assert_eq!(Balances::free_balance(sender), before + 30);
+		assert_last_event::<Test>(RawEvent::Refunded(id, sender, 30).into());
+	});
+}
+
+const GATEWAY: &[u8] = b"https://gateway.test/";
+const MANIFEST: &[u8] = br#"{ "job_mode": "batch", "job_total_tasks": 3 }"#;
+
+fn manifest_url(name: &str) -> Vec<u8> {
+	[GATEWAY, name.as_bytes()].concat()
+}
+
+fn expect_manifest_request(state: &mut sp_core::offchain::testing::OffchainState, name: &str, body: &[u8]) {
+	state.expect_request(PendingRequest {
+		method: "GET".into(),
+		uri: String::from_utf8(manifest_url(name)).unwrap(),
+		response: Some(body.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn canonical_escrow(name: &str) -> EscrowInfo<Moment, AccountId, Balance> {
+	EscrowBuilder::new()
+		.manifest_url(manifest_url(name))
+		.manifest_hash(manifest::hash(MANIFEST).unwrap().to_vec())
+		.manifest_hash_algorithm(ManifestHashAlgorithm::Canonical)
+		.build()
+}
+
+/// Register a keystore with a manifest checker key and return the account of the key.
+fn register_checker_key(ext: &mut sp_io::TestExternalities) -> AccountId {
+	let keystore = KeyStore::new();
+	let public = keystore.write().sr25519_generate_new(KEY_TYPE, None).unwrap();
+	ext.register_extension(KeystoreExt(keystore));
+	TestPublic(public).into_account()
+}
+
+#[test]
+fn manifest_hash_algorithms_digest() {
+	assert_eq!(ManifestHashAlgorithm::Canonical.digest(MANIFEST), Some(manifest::hash(MANIFEST).unwrap()));
+	assert_eq!(ManifestHashAlgorithm::Canonical.digest(b"not json"), None);
+	assert_eq!(ManifestHashAlgorithm::Sha2_256.digest(MANIFEST), Some(sp_io::hashing::sha2_256(MANIFEST)));
+	assert_eq!(ManifestHashAlgorithm::Keccak256.digest(MANIFEST), Some(sp_io::hashing::keccak_256(MANIFEST)));
+	assert_eq!(ManifestHashAlgorithm::Blake2_256.digest(MANIFEST), Some(sp_io::hashing::blake2_256(MANIFEST)));
+	assert_eq!(ManifestHashAlgorithm::Other.digest(MANIFEST), None);
+	assert!(!ManifestHashAlgorithm::Other.is_supported());
+}
+
+#[test]
+fn only_supported_manifest_hashes_are_checked() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		set_manifest_gateways(vec![GATEWAY.to_vec()]);
+		// Hashes of other algorithms are stored unchecked.
+		store_default_escrow(0, sender);
+		assert!(!Escrow::manifest_check_pending(0));
+		assert_eq!(Escrow::escrow(0).unwrap().manifest_hash_algorithm, ManifestHashAlgorithm::Other);
+
+		let escrow = EscrowBuilder::new()
+			.manifest_hash(vec![1; 33])
+			.manifest_hash_algorithm(ManifestHashAlgorithm::Sha2_256)
+			.build();
+		assert_noop!(create_escrow_noop(sender, &escrow), Error::<Test>::InvalidManifestHash);
+
+		assert_ok!(create_escrow_noop(sender, &canonical_escrow("ok.json")));
+		assert!(Escrow::manifest_check_pending(1));
+		assert_eq!(Escrow::escrow(1).unwrap().manifest_hash_algorithm, ManifestHashAlgorithm::Canonical);
+	});
+}
+
+#[test]
+fn offchain_worker_reports_manifest_checks() {
+	let (offchain, state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	let checker = register_checker_key(&mut ext);
+	ext.execute_with(|| {
+		let sender = 1;
+		// Escrows created without gateways are not checked.
+		store_default_escrow(0, sender);
+		assert!(!Escrow::manifest_check_pending(0));
+
+		set_manifest_gateways(vec![GATEWAY.to_vec()]);
+		assert_ok!(create_escrow_noop(sender, &canonical_escrow("ok.json")));
+		assert_ok!(create_escrow_noop(sender, &canonical_escrow("swapped.json")));
+		assert!(Escrow::manifest_check_pending(1));
+		assert!(Escrow::manifest_check_pending(2));
+
+		// Nodes without the key of a manifest checker do not report.
+		Escrow::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+
+		add_manifest_checker(checker);
+		expect_manifest_request(&mut state.write(), "ok.json", br#"{"job_total_tasks":3,"job_mode":"batch"}"#);
+		expect_manifest_request(&mut state.write(), "swapped.json", br#"{"job_mode":"online"}"#);
+		Escrow::offchain_worker(1);
+		let calls: Vec<_> = pool_state.write().transactions.drain(..)
+			.map(|tx| {
+				let tx = Extrinsic::decode(&mut &tx[..]).unwrap();
+				assert!(tx.signature.is_some());
+				tx.call
+			})
+			.collect();
+		assert_eq!(calls.len(), 2);
+		assert!(calls.contains(&Call::report_manifest_check(1, true)));
+		assert!(calls.contains(&Call::report_manifest_check(2, false)));
+
+		// Reports are not submitted again while they may still be included.
+		let escrow = EscrowBuilder::new()
+			.manifest_url(b"https://elsewhere.test/ok.json".to_vec())
+			.manifest_hash(manifest::hash(MANIFEST).unwrap().to_vec())
+			.manifest_hash_algorithm(ManifestHashAlgorithm::Canonical)
+			.build();
+		assert_ok!(create_escrow_noop(sender, &escrow));
+		Escrow::offchain_worker(2);
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert_eq!(Extrinsic::decode(&mut &tx[..]).unwrap().call, Call::report_manifest_check(3, false));
+		assert!(pool_state.read().transactions.is_empty());
+
+		// The manifests of closed escrows are not fetched.
+		assert_ok!(create_escrow_noop(sender, &canonical_escrow("ok.json")));
+		Escrows::<Test>::mutate(4, |escrow| escrow.as_mut().unwrap().status = EscrowStatus::Cancelled);
+		let requests = state.read().requests.len();
+		Escrow::offchain_worker(2);
+		assert_eq!(state.read().requests.len(), requests);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn manifest_fetches_give_up_after_attempts() {
+	let (offchain, _) = TestOffchainExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainExt::new(offchain));
+	ext.execute_with(|| {
+		// Timeouts and IO errors are retried indefinitely.
+		for _ in 0..20 {
+			assert!(!Escrow::note_failed_fetch(0, &http::Error::DeadlineReached));
+			assert!(!Escrow::note_failed_fetch(0, &http::Error::IoError));
+		}
+		for _ in 1..10 {
+			assert!(!Escrow::note_failed_fetch(0, &http::Error::Unknown));
+		}
+		assert!(Escrow::note_failed_fetch(0, &http::Error::Unknown));
+		// Attempts are counted per escrow.
+		assert!(!Escrow::note_failed_fetch(1, &http::Error::Unknown));
+	});
+}
+
+#[test]
+fn report_manifest_check_flags_escrow() {
+	new_test_ext().execute_with(|| {
+		let sender = 1;
+		let checker = 7;
+		add_manifest_checker(checker);
+		set_manifest_gateways(vec![GATEWAY.to_vec()]);
+		assert_ok!(create_escrow(sender, &canonical_escrow("ok.json")));
+		assert_ok!(create_escrow_noop(sender, &canonical_escrow("swapped.json")));
+		assert_noop!(Escrow::report_manifest_check(Origin::none(), 0, true), DispatchError::BadOrigin);
+		assert_noop!(
+			Escrow::report_manifest_check(Origin::signed(sender), 0, true),
+			Error::<Test>::NotManifestChecker
+		);
+
+		assert_ok!(Escrow::report_manifest_check(Origin::signed(checker), 0, true));
+		assert_eq!(Escrow::manifest_check(0), Some(true));
+		assert!(!Escrow::manifest_check_pending(0));
+		assert_last_event::<Test>(RawEvent::<AccountId, Balance>::ManifestChecked(0, true).into());
+		assert_noop!(
+			Escrow::report_manifest_check(Origin::signed(checker), 0, false),
+			Error::<Test>::ManifestCheckNotPending
+		);
+		assert_ok!(Escrow::fund(Origin::signed(sender), 0, 10));
+
+		assert_ok!(Escrow::report_manifest_check(Origin::signed(checker), 1, false));
+		assert_noop!(Escrow::fund(Origin::signed(sender), 1, 10), Error::<Test>::ManifestMismatch);
+		assert_noop!(
+			Escrow::note_intermediate_results(Origin::signed(sender), 1, b"url".to_vec(), b"hash".to_vec()),
+			Error::<Test>::ManifestMismatch
+		);
+		// The launcher can still get the funds back.
+		assert_ok!(Escrow::abort(Origin::signed(sender), 1));
+		assert_eq!(Escrow::manifest_check(1), None);
+	});
+}
+
+#[test]
+fn runtime_upgrade_migrates_v1_escrows() {
+	new_test_ext().execute_with(|| {
+		let old: EscrowInfoV1<Moment, AccountId> = EscrowInfoV1 {
+			status: EscrowStatus::Pending,
+			end_time: 1000,
+			manifest_url: b"some.url".to_vec(),
+			manifest_hash: b"0xdev".to_vec(),
+			reputation_oracle: 3,
+			recording_oracle: 4,
+			reputation_oracle_stake: Percent::from_percent(10),
+			recording_oracle_stake: Percent::from_percent(10),
+			canceller: 1,
+			account: 10,
+			factory: 0,
+		};
+		for id in 0..2 {
+			unhashed::put(&Escrows::<Test>::hashed_key_for(id), &old);
+		}
+		// Paid out without final results.
+		let paid = EscrowInfoV1 { status: EscrowStatus::Paid, ..old.clone() };
+		unhashed::put(&Escrows::<Test>::hashed_key_for(2), &paid);
+		FinalResults::insert(1, ResultInfo {
+			results_url: b"results.url".to_vec(),
+			results_hash: b"0xdev".to_vec(),
+		});
+		assert_eq!(Escrow::storage_version(), Releases::V1);
+
+		Escrow::on_runtime_upgrade();
+
+		assert_eq!(Escrow::storage_version(), Releases::V2);
+		let escrow = Escrow::escrow(0).expect("escrow should be migrated");
+		assert_eq!(escrow.status, EscrowStatus::Pending);
+		assert_eq!(escrow.canceller, 1);
+		assert_eq!(escrow.account, 10);
+		assert_eq!(escrow.manifest_hash_algorithm, ManifestHashAlgorithm::Other);
+		assert_eq!(
+			escrow.oracles.iter().map(|o| (o.account, o.role, o.fee)).collect::<Vec<_>>(),
+			vec![
+				(3, OracleRole::Reputation, Percent::from_percent(10)),
+				(4, OracleRole::Recording, Percent::from_percent(10)),
+			]
+		);
+		assert_eq!(Escrow::escrows_of(1, EscrowParty::Launcher, None, 10), vec![0, 1, 2]);
+		assert_eq!(Escrow::escrows_of(4, EscrowParty::Oracle(OracleRole::Recording), None, 10), vec![0, 1, 2]);
+		assert!(!Escrow::results_accepted(0));
+		assert!(Escrow::results_accepted(1));
+		assert!(Escrow::results_accepted(2));
+		// The paid escrow can be completed.
+		TrustedHandlers::<Test>::insert(2, 1, true);
+		assert_ok!(Escrow::complete(Origin::signed(1), 2));
+		assert_eq!(Escrow::escrow(2).unwrap().status, EscrowStatus::Complete);
+
+		// Migrated escrows are not touched again.
+		let weight = Escrow::on_runtime_upgrade();
+		let db_weight: RuntimeDbWeight = <Test as frame_system::Trait>::DbWeight::get();
+		assert_eq!(weight, db_weight.reads(1));
+		assert_eq!(Escrow::escrow(0), Some(escrow));
+	});
+}
@@ -0,0 +1,126 @@
+//! RPC interface for the key-value store pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, NumberFor}};
+
+pub use pallet_kvstore_runtime_api::KVStoreApi as KVStoreRuntimeApi;
+
+/// The maximum number of keys returned by `kvstore_keys` or read by `kvstore_getMany`.
+pub const MAX_KEYS_LIMIT: u32 = 1000;
+
+/// Error code of failed runtime calls.
+const RUNTIME_ERROR: i64 = 1;
+/// Error code of requests for more than `MAX_KEYS_LIMIT` keys.
+const TOO_MANY_KEYS: i64 = 2;
+
+#[rpc]
+pub trait KVStoreApi<BlockHash, AccountId> {
+	/// Get at most `limit` keys stored by `account`, following `start_key` if given.
+	///
+	/// `limit` is capped at `MAX_KEYS_LIMIT`.
+	#[rpc(name = "kvstore_keys")]
+	fn keys(
+		&self,
+		account: AccountId,
+		start_key: Option<Bytes>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Bytes>>;
+
+	/// Get the value of `key` stored by `account` at the block `at`, or the best block.
+	///
+	/// Returns `null` if there is no value.
+	#[rpc(name = "kvstore_get")]
+	fn get(&self, account: AccountId, key: Bytes, at: Option<BlockHash>) -> Result<Option<Bytes>>;
+
+	/// Get the values of `keys` stored by `account` at the block `at`, or the best block.
+	///
+	/// At most `MAX_KEYS_LIMIT` keys can be read at once.
+	#[rpc(name = "kvstore_getMany")]
+	fn get_many(
+		&self,
+		account: AccountId,
+		keys: Vec<Bytes>,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Option<Bytes>>>;
+}
+
+/// Implements the key-value store RPC methods via the runtime API.
+pub struct KVStore<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> KVStore<C, B> {
+	/// Create a new `KVStore` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId> KVStoreApi<<Block as BlockT>::Hash, AccountId> for KVStore<C, Block> where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KVStoreRuntimeApi<Block, AccountId, NumberFor<Block>>,
+	AccountId: Codec,
+{
+	fn keys(
+		&self,
+		account: AccountId,
+		start_key: Option<Bytes>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.keys(&at, account, start_key.map(|key| key.to_vec()), limit.min(MAX_KEYS_LIMIT))
+			.map(|keys| keys.into_iter().map(Bytes).collect())
+			.map_err(|e| runtime_error("Unable to query keys.", e))
+	}
+
+	fn get(&self, account: AccountId, key: Bytes, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get(&at, account, key.to_vec())
+			.map(|value| value.map(Bytes))
+			.map_err(|e| runtime_error("Unable to query value.", e))
+	}
+
+	fn get_many(
+		&self,
+		account: AccountId,
+		keys: Vec<Bytes>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Option<Bytes>>> {
+		if keys.len() > MAX_KEYS_LIMIT as usize {
+			return Err(RpcError {
+				code: ErrorCode::ServerError(TOO_MANY_KEYS),
+				message: format!("At most {} keys can be read at once.", MAX_KEYS_LIMIT),
+				data: None,
+			});
+		}
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_many(&at, account, keys.into_iter().map(|key| key.to_vec()).collect())
+			.map(|values| values.into_iter().map(|value| value.map(Bytes)).collect())
+			.map_err(|e| runtime_error("Unable to query values.", e))
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
@@ -0,0 +1,38 @@
+//! Runtime API definition for the key-value store pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_kvstore::{ValueFormat, Version};
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// Queries into the key-value store that are not convenient to do via raw storage access.
+	pub trait KVStoreApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// Get at most `limit` keys stored by `account`, following `start_key` if given.
+		///
+		/// Keys are in storage order. The last key of a page is the `start_key` of the next one.
+		fn keys(account: AccountId, start_key: Option<Vec<u8>>, limit: u32) -> Vec<Vec<u8>>;
+
+		/// Get the value of `key` stored by `account`, `None` if there is none.
+		fn get(account: AccountId, key: Vec<u8>) -> Option<Vec<u8>>;
+
+		/// Get the values of `keys` stored by `account`, in the order of `keys`.
+		fn get_many(account: AccountId, keys: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>>;
+
+		/// Get the kept versions of `key` stored by `account`, oldest first.
+		fn history(account: AccountId, key: Vec<u8>) -> Vec<Version<BlockNumber>>;
+
+		/// Get the value of `key` stored by `account` at the end of the block `at`.
+		///
+		/// Returns `None` if there was no value, `at` is older than the kept history or the key was
+		/// removed or expired since.
+		fn value_at(account: AccountId, key: Vec<u8>, at: BlockNumber) -> Option<Vec<u8>>;
+
+		/// Get the format registered for `key`, if any.
+		fn schema(key: Vec<u8>) -> Option<ValueFormat>;
+	}
+}
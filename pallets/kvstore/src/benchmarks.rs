@@ -4,9 +4,12 @@ use super::*;
 use sp_std::prelude::*;
 
 use frame_system::{RawOrigin, EventRecord};
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::OnInitialize;
 use crate::Module as KVStore;
 
+const SEED: u32 = 0;
+
 fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
 	let events = frame_system::Module::<T>::events();
 	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
@@ -30,7 +33,180 @@ benchmarks! {
 	} : set(RawOrigin::Signed(caller.clone()), key.clone(), value.clone())
 	verify {
 		assert_eq!(KVStore::<T>::get(&caller, &key), value);
-		assert_last_event::<T>(RawEvent::Stored(caller, key, value).into())
+		assert_last_event::<T>(KVStore::<T>::stored_event(caller, key, value, None).into())
+	}
+
+	remove {
+		let k in 1..(T::StringLimit::get() as u32);
+		let caller: T::AccountId = whitelisted_caller();
+
+		let key = vec![111; k as usize];
+		// The whole history of the key is dropped.
+		for _ in 0..T::HistoryDepth::get() {
+			KVStore::<T>::set_for_account(&caller, &caller, &key, &vec![111; T::StringLimit::get()])?;
+		}
+
+	} : remove(RawOrigin::Signed(caller.clone()), key.clone())
+	verify {
+		assert!(!Storage::<T>::contains_key(&caller, &key));
+		assert!(!History::<T>::contains_key(&caller, &key));
+		assert_last_event::<T>(RawEvent::Removed(caller, key).into())
+	}
+
+	set_many {
+		let n in 1..T::BatchLimit::get();
+		let caller: T::AccountId = whitelisted_caller();
+
+		// Keys and values of maximum length, the key is prefixed with the entry index.
+		let entries: Vec<(Vec<u8>, Vec<u8>)> = (0..n).map(|i| {
+			let mut key = i.encode();
+			key.resize(T::StringLimit::get(), 111);
+			(key, vec![111; T::StringLimit::get()])
+		}).collect();
+		let (last_key, last_value) = entries[entries.len() - 1].clone();
+
+	} : set_many(RawOrigin::Signed(caller.clone()), entries)
+	verify {
+		assert_eq!(KVStore::<T>::get(&caller, &last_key), last_value);
+		assert_last_event::<T>(KVStore::<T>::stored_event(caller, last_key, last_value, None).into())
+	}
+
+	grant {
+		let caller: T::AccountId = whitelisted_caller();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		// Other prefixes of maximum length are compared to the new one.
+		let prefixes: Vec<Vec<u8>> = (1..T::DelegationsLimit::get())
+			.map(|i| vec![i as u8; T::StringLimit::get()])
+			.collect();
+		Delegations::<T>::insert(&caller, &delegate, prefixes);
+		let key_prefix = vec![0; T::StringLimit::get()];
+
+	} : grant(RawOrigin::Signed(caller.clone()), delegate.clone(), key_prefix.clone())
+	verify {
+		assert!(KVStore::<T>::delegations(&caller, &delegate).contains(&key_prefix));
+		assert_last_event::<T>(RawEvent::Granted(caller, delegate, key_prefix).into())
+	}
+
+	revoke {
+		let caller: T::AccountId = whitelisted_caller();
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let prefixes: Vec<Vec<u8>> = (0..T::DelegationsLimit::get())
+			.map(|i| vec![i as u8; T::StringLimit::get()])
+			.collect();
+		let key_prefix = prefixes[prefixes.len() - 1].clone();
+		Delegations::<T>::insert(&caller, &delegate, prefixes);
+
+	} : revoke(RawOrigin::Signed(caller.clone()), delegate.clone(), key_prefix.clone())
+	verify {
+		assert!(!KVStore::<T>::delegations(&caller, &delegate).contains(&key_prefix));
+		assert_last_event::<T>(RawEvent::Revoked(caller, delegate, key_prefix).into())
+	}
+
+	set_for {
+		let k in 1..(T::StringLimit::get() as u32);
+		let v in 1..(T::StringLimit::get() as u32);
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+
+		// The matching prefix is checked last.
+		let mut prefixes: Vec<Vec<u8>> = (1..T::DelegationsLimit::get())
+			.map(|i| vec![i as u8; T::StringLimit::get()])
+			.collect();
+		prefixes.push(vec![0; k as usize]);
+		Delegations::<T>::insert(&owner, &caller, prefixes);
+		let key = vec![0; k as usize];
+		let value = vec![111; v as usize];
+
+	} : set_for(RawOrigin::Signed(caller), owner.clone(), key.clone(), value.clone())
+	verify {
+		assert_eq!(KVStore::<T>::get(&owner, &key), value);
+		assert_last_event::<T>(KVStore::<T>::stored_event(owner, key, value, None).into())
+	}
+
+	remove_for {
+		let k in 1..(T::StringLimit::get() as u32);
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+
+		let mut prefixes: Vec<Vec<u8>> = (1..T::DelegationsLimit::get())
+			.map(|i| vec![i as u8; T::StringLimit::get()])
+			.collect();
+		prefixes.push(vec![0; k as usize]);
+		Delegations::<T>::insert(&owner, &caller, prefixes);
+		let key = vec![0; k as usize];
+		KVStore::<T>::set_for_account(&owner, &owner, &key, &vec![111; T::StringLimit::get()])?;
+
+	} : remove_for(RawOrigin::Signed(caller), owner.clone(), key.clone())
+	verify {
+		assert!(!Storage::<T>::contains_key(&owner, &key));
+		assert_last_event::<T>(RawEvent::Removed(owner, key).into())
+	}
+
+	set_with_ttl {
+		let k in 1..(T::StringLimit::get() as u32);
+		let v in 1..(T::StringLimit::get() as u32);
+		let caller: T::AccountId = whitelisted_caller();
+
+		let key = vec![111; k as usize];
+		let value = vec![111; v as usize];
+		let ttl: T::BlockNumber = 10u32.into();
+		// Events are only deposited from block 1 on, the expiry must be known before.
+		frame_system::Module::<T>::set_block_number(1u32.into());
+		let expiry = frame_system::Module::<T>::block_number() + ttl;
+		// The history is full, the key expires at another block and the sender's expiries almost.
+		for _ in 0..T::HistoryDepth::get() {
+			KVStore::<T>::set_for_account(&caller, &caller, &key, &vec![111; T::StringLimit::get()])?;
+		}
+		KVStore::<T>::set_with_ttl(RawOrigin::Signed(caller.clone()).into(), key.clone(), vec![111], ttl + ttl)?;
+		let expiring: Vec<Vec<u8>> = (1..T::MaxExpiriesPerAccount::get())
+			.map(|i| vec![i as u8; T::StringLimit::get()])
+			.collect();
+		Expiries::<T>::insert(expiry, &caller, expiring);
+		ExpiryCount::<T>::insert(expiry, T::MaxExpiriesPerBlock::get() - 1);
+
+	} : set_with_ttl(RawOrigin::Signed(caller.clone()), key.clone(), value.clone(), ttl)
+	verify {
+		assert_eq!(KVStore::<T>::get(&caller, &key), value);
+		assert_last_event::<T>(KVStore::<T>::stored_event(caller, key, value, Some(expiry)).into())
+	}
+
+	on_initialize {
+		// Every value is of another account, the most expensive way to expire values.
+		let n in 0..T::MaxExpiriesPerBlock::get();
+		let ttl: T::BlockNumber = 10u32.into();
+		let expiry = frame_system::Module::<T>::block_number() + ttl;
+		let key = vec![111];
+		let owners: Vec<T::AccountId> = (0..n).map(|i| account("owner", i, SEED)).collect();
+		for owner in owners.iter() {
+			KVStore::<T>::set_with_ttl(RawOrigin::Signed(owner.clone()).into(), key.clone(), vec![111], ttl)?;
+		}
+
+	} : { KVStore::<T>::on_initialize(expiry); }
+	verify {
+		for owner in owners {
+			assert!(!Storage::<T>::contains_key(&owner, &key));
+		}
+	}
+
+	register_schema {
+		let k in 1..(T::StringLimit::get() as u32);
+		let origin = T::SchemaOrigin::successful_origin();
+		let key = vec![111; k as usize];
+	} : { KVStore::<T>::register_schema(origin, key.clone(), ValueFormat::Json)? }
+	verify {
+		assert_eq!(KVStore::<T>::schema(&key), Some(ValueFormat::Json));
+		assert_last_event::<T>(RawEvent::SchemaRegistered(key, ValueFormat::Json).into())
+	}
+
+	unregister_schema {
+		let k in 1..(T::StringLimit::get() as u32);
+		let origin = T::SchemaOrigin::successful_origin();
+		let key = vec![111; k as usize];
+		Schemas::insert(&key, ValueFormat::Json);
+	} : { KVStore::<T>::unregister_schema(origin, key.clone())? }
+	verify {
+		assert_eq!(KVStore::<T>::schema(&key), None);
+		assert_last_event::<T>(RawEvent::SchemaUnregistered(key).into())
 	}
 
 }
@@ -48,4 +224,44 @@ mod tests {
 				});
 		}
 
+		#[test]
+		fn test_remove() {
+				new_test_ext().execute_with(|| {
+					assert_ok!(test_benchmark_remove::<Test>());
+				});
+		}
+
+		#[test]
+		fn test_set_many() {
+				new_test_ext().execute_with(|| {
+					assert_ok!(test_benchmark_set_many::<Test>());
+				});
+		}
+
+		#[test]
+		fn test_delegation() {
+				new_test_ext().execute_with(|| {
+					assert_ok!(test_benchmark_grant::<Test>());
+					assert_ok!(test_benchmark_revoke::<Test>());
+					assert_ok!(test_benchmark_set_for::<Test>());
+					assert_ok!(test_benchmark_remove_for::<Test>());
+				});
+		}
+
+		#[test]
+		fn test_ttl() {
+				new_test_ext().execute_with(|| {
+					assert_ok!(test_benchmark_set_with_ttl::<Test>());
+					assert_ok!(test_benchmark_on_initialize::<Test>());
+				});
+		}
+
+		#[test]
+		fn test_schemas() {
+				new_test_ext().execute_with(|| {
+					assert_ok!(test_benchmark_register_schema::<Test>());
+					assert_ok!(test_benchmark_unregister_schema::<Test>());
+				});
+		}
+
 }
\ No newline at end of file
@@ -1,8 +1,15 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_std::prelude::*;
-use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, weights::Weight, traits::Get};
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, weights::Weight,
+	traits::{EnsureOrigin, Get}, Blake2_128Concat, Blake2_256, IterableStorageDoubleMap, StorageHasher,
+};
 use frame_system::ensure_signed;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::{Saturating, Zero};
 
 #[cfg(test)]
 mod mock;
@@ -15,11 +22,171 @@ mod benchmarks;
 pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 	type StringLimit: Get<usize>;
+	/// The maximum number of entries written by `set_many`.
+	type BatchLimit: Get<u32>;
+	/// The maximum number of key prefixes an owner can grant to a delegate.
+	type DelegationsLimit: Get<u32>;
+	/// The number of versions kept per key, including the current one.
+	type HistoryDepth: Get<u32>;
+	/// The maximum number of values of an account expiring in the same block.
+	type MaxExpiriesPerAccount: Get<u32>;
+	/// The maximum number of values expiring in the same block, bounding `on_initialize`.
+	type MaxExpiriesPerBlock: Get<u32>;
+	/// Whether events carry stored values or only their hashes.
+	type EventMode: Get<EventMode>;
+	/// The origin allowed to register the formats of keys.
+	type SchemaOrigin: EnsureOrigin<Self::Origin>;
 	type WeightInfo: WeightInfo;
 }
 
+/// The blake2_256 hash of a value, reported instead of the value in `EventMode::Hashed`.
+pub type ValueHash = [u8; 32];
+
+/// How stored values are reported in events.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EventMode {
+	/// Emit `Stored` and `StoredWithTtl` with the full value.
+	Full,
+	/// Emit `StoredHash` and `StoredHashWithTtl` with the blake2_256 hash and length of the value.
+	Hashed,
+}
+
 pub trait WeightInfo {
 	fn set(k: u32, v: u32) -> Weight;
+	fn remove(k: u32) -> Weight;
+	fn set_many(n: u32) -> Weight;
+	fn grant() -> Weight;
+	fn revoke() -> Weight;
+	fn set_for(k: u32, v: u32) -> Weight;
+	fn remove_for(k: u32) -> Weight;
+	fn set_with_ttl(k: u32, v: u32) -> Weight;
+	fn on_initialize(n: u32) -> Weight;
+	fn register_schema(k: u32) -> Weight;
+	fn unregister_schema(k: u32) -> Weight;
+}
+
+/// The format values of a registered key must have.
+#[derive(Copy, Clone, Encode, Decode, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ValueFormat {
+	/// A URL like `https://example.com/path`, with a scheme and a non-empty remainder.
+	Url,
+	/// A PEM or ASCII-armored public key, or a raw 32, 33 or 65 byte public key.
+	PublicKey,
+	/// A JSON document.
+	Json,
+	/// A decimal `u64`.
+	U64,
+	/// A SCALE encoded account id.
+	AccountId,
+}
+
+impl ValueFormat {
+	/// Whether `value` has this format.
+	pub fn validate<AccountId: Decode>(&self, value: &[u8]) -> bool {
+		match self {
+			ValueFormat::Url => as_text(value).map_or(false, is_url),
+			ValueFormat::PublicKey => match as_text(value) {
+				Some(text) if text.trim_start().starts_with("-----BEGIN ") => is_armored_public_key(text),
+				_ => matches!(value.len(), 32 | 33 | 65),
+			},
+			ValueFormat::Json => as_text(value).map_or(false, |text| lite_json::parse_json(text).is_ok()),
+			ValueFormat::U64 => as_text(value).map_or(false, |text| {
+				!text.is_empty() && text.bytes().all(|b| b.is_ascii_digit()) && text.parse::<u64>().is_ok()
+			}),
+			ValueFormat::AccountId => {
+				let mut input = value;
+				AccountId::decode(&mut input).is_ok() && input.is_empty()
+			}
+		}
+	}
+}
+
+fn as_text(value: &[u8]) -> Option<&str> {
+	sp_std::str::from_utf8(value).ok()
+}
+
+fn is_url(text: &str) -> bool {
+	if text.chars().any(|c| c.is_whitespace() || c.is_control()) {
+		return false;
+	}
+	let mut parts = text.splitn(2, "://");
+	let (scheme, rest) = match (parts.next(), parts.next()) {
+		(Some(scheme), Some(rest)) => (scheme, rest),
+		_ => return false,
+	};
+	let valid_scheme = scheme.chars().next().map_or(false, |c| c.is_ascii_alphabetic())
+		&& scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+	valid_scheme && !rest.is_empty() && !rest.starts_with('/')
+}
+
+/// Checks the `-----BEGIN/END ... PUBLIC KEY...-----` lines and the base64 body in between.
+///
+/// Armor headers like `Version: ...` and the `=` prefixed checksum of PGP keys are allowed.
+fn is_armored_public_key(text: &str) -> bool {
+	let lines: Vec<&str> = text.trim().lines().map(|line| line.trim()).collect();
+	if lines.len() < 3 {
+		return false;
+	}
+	let label = match lines[0].strip_prefix("-----BEGIN ").and_then(|l| l.strip_suffix("-----")) {
+		Some(label) if label.contains("PUBLIC KEY") => label,
+		_ => return false,
+	};
+	let end = lines[lines.len() - 1];
+	if end.strip_prefix("-----END ").and_then(|l| l.strip_suffix("-----")) != Some(label) {
+		return false;
+	}
+	let body: Vec<&str> = lines[1..lines.len() - 1].iter()
+		.filter(|line| !line.is_empty() && !line.contains(": "))
+		.cloned()
+		.collect();
+	!body.is_empty() && body.iter().all(|line| {
+		line.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=')
+	})
+}
+
+/// The well-known key under which accounts publish their public key for encryption.
+pub const PUBLIC_KEY: &[u8] = b"public_key";
+
+/// Looks up the public keys accounts published for encrypting data to them.
+pub trait KeyProvider<AccountId> {
+	/// The public key of `who`, if they published one in a valid format.
+	fn public_key(who: &AccountId) -> Option<Vec<u8>>;
+
+	/// Publish `key` as the public key of `who`.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_public_key(who: &AccountId, key: Vec<u8>);
+}
+
+impl<AccountId> KeyProvider<AccountId> for () {
+	fn public_key(_who: &AccountId) -> Option<Vec<u8>> {
+		None
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_public_key(_who: &AccountId, _key: Vec<u8>) {}
+}
+
+/// A value of a key and the blocks it was stored for.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub struct Version<BlockNumber> {
+	/// The stored value.
+	pub value: Vec<u8>,
+	/// The block the value was set in.
+	pub since: BlockNumber,
+	/// The block the value was overwritten, removed or expired in. `None` while it is current
+	/// without time-to-live.
+	pub until: Option<BlockNumber>,
+}
+
+impl<BlockNumber: PartialOrd> Version<BlockNumber> {
+	/// Whether this was the value of its key at the end of the block `at`.
+	///
+	/// That is, it was set in or before `at` and not yet overwritten, removed or expired.
+	fn is_valid_at(&self, at: &BlockNumber) -> bool {
+		self.since <= *at && self.until.as_ref().map_or(true, |until| at < until)
+	}
 }
 
 decl_storage! {
@@ -30,13 +197,60 @@ decl_storage! {
 		/// potential attackers. (Using pallets will have to keep to that constraint, though.)
 		Storage get(fn get):
 			double_map hasher(twox_64_concat) T::AccountId, hasher(blake2_128_concat) Vec<u8> => Vec<u8>;
+
+		/// The key prefixes a delegate may write to in the namespace of an owner. [owner, delegate]
+		///
+		/// An empty prefix grants the whole namespace.
+		Delegations get(fn delegations):
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId => Vec<Vec<u8>>;
+
+		/// The latest `HistoryDepth` versions of a key, oldest first.
+		///
+		/// The history is dropped with the key when it is removed or expires.
+		History get(fn history):
+			double_map hasher(twox_64_concat) T::AccountId, hasher(blake2_128_concat) Vec<u8>
+			=> Vec<Version<T::BlockNumber>>;
+
+		/// The keys of an account expiring at a block. Pruned in `on_initialize` of that block.
+		///
+		/// Keys are taken out again when they are overwritten or removed before.
+		Expiries:
+			double_map hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) T::AccountId => Vec<Vec<u8>>;
+
+		/// The number of values of all accounts expiring at a block, at most `MaxExpiriesPerBlock`.
+		ExpiryCount get(fn expiry_count): map hasher(twox_64_concat) T::BlockNumber => u32;
+
+		/// The formats of registered keys. Values written to them are validated, in all namespaces.
+		Schemas get(fn schema): map hasher(blake2_128_concat) Vec<u8> => Option<ValueFormat>;
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		BlockNumber = <T as frame_system::Trait>::BlockNumber,
+	{
 		/// Stored a value at (account id, key). [account id, key, value]
 		Stored(AccountId, Vec<u8>, Vec<u8>),
+		/// Stored a value at (account id, key) that expires at a block. [account id, key, value, expiry]
+		StoredWithTtl(AccountId, Vec<u8>, Vec<u8>, BlockNumber),
+		/// Stored a value at (account id, key). [account id, key, blake2_256 of value, value length]
+		StoredHash(AccountId, Vec<u8>, ValueHash, u32),
+		/// Stored a value at (account id, key) that expires at a block.
+		/// [account id, key, blake2_256 of value, value length, expiry]
+		StoredHashWithTtl(AccountId, Vec<u8>, ValueHash, u32, BlockNumber),
+		/// The value at (account id, key) expired and was removed. [account id, key]
+		Expired(AccountId, Vec<u8>),
+		/// The format of a key was registered. [key, format]
+		SchemaRegistered(Vec<u8>, ValueFormat),
+		/// The format of a key was unregistered. [key]
+		SchemaUnregistered(Vec<u8>),
+		/// Removed the value at (account id, key). [account id, key]
+		Removed(AccountId, Vec<u8>),
+		/// An owner allowed a delegate to write keys with a prefix. [owner, delegate, key prefix]
+		Granted(AccountId, AccountId, Vec<u8>),
+		/// An owner revoked a granted key prefix. [owner, delegate, key prefix]
+		Revoked(AccountId, AccountId, Vec<u8>),
 	}
 );
 
@@ -46,6 +260,28 @@ decl_error! {
 		KeyTooLong,
 		/// The given value exceeds `StringLimit`
 		ValueTooLong,
+		/// There is no value stored under the given key
+		UnknownKey,
+		/// More entries than `BatchLimit` were given
+		TooManyEntries,
+		/// The sender may not write the key in the owner's namespace
+		NotDelegated,
+		/// The key prefix is already granted to the delegate
+		AlreadyGranted,
+		/// The key prefix is not granted to the delegate
+		NotGranted,
+		/// The delegate already has `DelegationsLimit` key prefixes
+		TooManyDelegations,
+		/// The time-to-live is zero
+		ZeroTtl,
+		/// `MaxExpiriesPerAccount` values of the sender already expire at the requested block
+		TooManyExpiries,
+		/// `MaxExpiriesPerBlock` values already expire at the requested block
+		BlockExpiriesFull,
+		/// The value does not have the format registered for the key
+		InvalidFormat,
+		/// No format is registered for the key
+		UnknownSchema,
 	}
 }
 
@@ -55,28 +291,377 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		/// Remove the values expiring at block `now` and their history.
+		///
+		/// At most `MaxExpiriesPerBlock` values expire in a block. The removal is paid for by `set_with_ttl`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut count = 0;
+			for (owner, keys) in Expiries::<T>::drain_prefix(now) {
+				for key in keys {
+					count += 1;
+					Storage::<T>::remove(&owner, &key);
+					History::<T>::remove(&owner, &key);
+					Self::deposit_event(RawEvent::Expired(owner.clone(), key));
+				}
+			}
+			ExpiryCount::<T>::remove(now);
+			T::WeightInfo::on_initialize(count)
+		}
+
 		/// Set the `value` under the sender's account id and `key`.
 		#[weight = T::WeightInfo::set(key.len() as u32, value.len() as u32)]
 		pub fn set(origin, key: Vec<u8>, value: Vec<u8>) -> dispatch::DispatchResult {
 			let acc = ensure_signed(origin)?;
 
-			Self::set_for_account(&acc, &key, &value)?;
+			Self::set_for_account(&acc, &acc, &key, &value)?;
 
-			Self::deposit_event(RawEvent::Stored(acc, key, value));
+			Self::deposit_stored(acc, key, value, None);
 			
 			Ok(())
 		}
+
+		/// Remove the value under the sender's account id and `key`, and its history.
+		#[weight = T::WeightInfo::remove(key.len() as u32)]
+		pub fn remove(origin, key: Vec<u8>) -> dispatch::DispatchResult {
+			let acc = ensure_signed(origin)?;
+
+			ensure!(Storage::<T>::contains_key(&acc, &key), Error::<T>::UnknownKey);
+			Self::erase(&acc, &key);
+
+			Self::deposit_event(RawEvent::Removed(acc, key));
+
+			Ok(())
+		}
+
+		/// Set all `entries` of keys and values under the sender's account id.
+		///
+		/// Either all or none of the entries are set.
+		#[weight = T::WeightInfo::set_many(entries.len() as u32)]
+		pub fn set_many(origin, entries: Vec<(Vec<u8>, Vec<u8>)>) -> dispatch::DispatchResult {
+			let acc = ensure_signed(origin)?;
+
+			ensure!(entries.len() as u32 <= T::BatchLimit::get(), Error::<T>::TooManyEntries);
+			for (key, value) in entries.iter() {
+				Self::ensure_valid(key, value)?;
+			}
+			for (key, value) in entries {
+				Self::write(&acc, &key, &value, None);
+				Self::deposit_stored(acc.clone(), key, value, None);
+			}
+
+			Ok(())
+		}
+
+		/// Allow `delegate` to write keys starting with `key_prefix` in the sender's namespace.
+		#[weight = T::WeightInfo::grant()]
+		pub fn grant(origin, delegate: T::AccountId, key_prefix: Vec<u8>) -> dispatch::DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			ensure!(key_prefix.len() <= T::StringLimit::get(), Error::<T>::KeyTooLong);
+			let mut prefixes = Delegations::<T>::get(&owner, &delegate);
+			ensure!(!prefixes.contains(&key_prefix), Error::<T>::AlreadyGranted);
+			ensure!((prefixes.len() as u32) < T::DelegationsLimit::get(), Error::<T>::TooManyDelegations);
+			prefixes.push(key_prefix.clone());
+			Delegations::<T>::insert(&owner, &delegate, prefixes);
+
+			Self::deposit_event(RawEvent::Granted(owner, delegate, key_prefix));
+
+			Ok(())
+		}
+
+		/// Revoke the permission of `delegate` to write keys starting with `key_prefix`.
+		///
+		/// Values the delegate already wrote are kept.
+		#[weight = T::WeightInfo::revoke()]
+		pub fn revoke(origin, delegate: T::AccountId, key_prefix: Vec<u8>) -> dispatch::DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			let mut prefixes = Delegations::<T>::get(&owner, &delegate);
+			let index = prefixes.iter().position(|p| p == &key_prefix).ok_or(Error::<T>::NotGranted)?;
+			prefixes.swap_remove(index);
+			if prefixes.is_empty() {
+				Delegations::<T>::remove(&owner, &delegate);
+			} else {
+				Delegations::<T>::insert(&owner, &delegate, prefixes);
+			}
+
+			Self::deposit_event(RawEvent::Revoked(owner, delegate, key_prefix));
+
+			Ok(())
+		}
+
+		/// Set the `value` under `owner` and `key` as a delegate of `owner`.
+		#[weight = T::WeightInfo::set_for(key.len() as u32, value.len() as u32)]
+		pub fn set_for(origin, owner: T::AccountId, key: Vec<u8>, value: Vec<u8>) -> dispatch::DispatchResult {
+			let writer = ensure_signed(origin)?;
+
+			Self::set_for_account(&writer, &owner, &key, &value)?;
+
+			Self::deposit_stored(owner, key, value, None);
+
+			Ok(())
+		}
+
+		/// Remove the value under `owner` and `key`, and its history, as a delegate of `owner`.
+		#[weight = T::WeightInfo::remove_for(key.len() as u32)]
+		pub fn remove_for(origin, owner: T::AccountId, key: Vec<u8>) -> dispatch::DispatchResult {
+			let writer = ensure_signed(origin)?;
+
+			Self::ensure_can_write(&writer, &owner, &key)?;
+			ensure!(Storage::<T>::contains_key(&owner, &key), Error::<T>::UnknownKey);
+			Self::erase(&owner, &key);
+
+			Self::deposit_event(RawEvent::Removed(owner, key));
+
+			Ok(())
+		}
+
+		/// Set the `value` under the sender's account id and `key` for `ttl` blocks.
+		///
+		/// The value is removed at the start of the block `ttl` blocks after the current one.
+		/// Setting or removing the key before cancels the expiry.
+		/// At most `MaxExpiriesPerAccount` values of an account and `MaxExpiriesPerBlock` values in
+		/// total can expire in the same block.
+		#[weight = T::WeightInfo::set_with_ttl(key.len() as u32, value.len() as u32)
+			.saturating_add(T::WeightInfo::on_initialize(1))]
+		pub fn set_with_ttl(origin, key: Vec<u8>, value: Vec<u8>, ttl: T::BlockNumber) -> dispatch::DispatchResult {
+			let acc = ensure_signed(origin)?;
+
+			Self::ensure_valid(&key, &value)?;
+			ensure!(!ttl.is_zero(), Error::<T>::ZeroTtl);
+			let expiry = <frame_system::Module<T>>::block_number().saturating_add(ttl);
+			let expiring = Expiries::<T>::get(expiry, &acc);
+			// An earlier expiry of the key is cancelled by the write.
+			let renewed = expiring.contains(&key);
+			let others = expiring.len() - renewed as usize;
+			ensure!((others as u32) < T::MaxExpiriesPerAccount::get(), Error::<T>::TooManyExpiries);
+			ensure!(
+				renewed || ExpiryCount::<T>::get(expiry) < T::MaxExpiriesPerBlock::get(),
+				Error::<T>::BlockExpiriesFull
+			);
+			Self::write(&acc, &key, &value, Some(expiry));
+			Expiries::<T>::append(expiry, &acc, &key);
+			ExpiryCount::<T>::mutate(expiry, |count| *count += 1);
+
+			Self::deposit_stored(acc, key, value, Some(expiry));
+
+			Ok(())
+		}
+
+		/// Register the `format` values written to `key` must have.
+		///
+		/// Values stored before are not checked. Requires `SchemaOrigin`.
+		#[weight = T::WeightInfo::register_schema(key.len() as u32)]
+		pub fn register_schema(origin, key: Vec<u8>, format: ValueFormat) -> dispatch::DispatchResult {
+			T::SchemaOrigin::ensure_origin(origin)?;
+
+			ensure!(key.len() <= T::StringLimit::get(), Error::<T>::KeyTooLong);
+			Schemas::insert(&key, format);
+
+			Self::deposit_event(RawEvent::SchemaRegistered(key, format));
+
+			Ok(())
+		}
+
+		/// Stop validating the values written to `key`.
+		///
+		/// Requires `SchemaOrigin`.
+		#[weight = T::WeightInfo::unregister_schema(key.len() as u32)]
+		pub fn unregister_schema(origin, key: Vec<u8>) -> dispatch::DispatchResult {
+			T::SchemaOrigin::ensure_origin(origin)?;
+
+			ensure!(Schemas::contains_key(&key), Error::<T>::UnknownSchema);
+			Schemas::remove(&key);
+
+			Self::deposit_event(RawEvent::SchemaUnregistered(key));
+
+			Ok(())
+		}
 	}
 }
 
 impl <T: Trait> Module<T> {
-	/// Set the given `value` in the double map under `acc` and `key`.
-	pub fn set_for_account(acc: &T::AccountId, key: &[u8], value: &[u8]) -> dispatch::DispatchResult {
+	/// Set the given `value` in the double map under `owner` and `key`.
+	///
+	/// `writer` has to be the owner or a delegate granted a prefix of `key`.
+	pub fn set_for_account(
+		writer: &T::AccountId,
+		owner: &T::AccountId,
+		key: &[u8],
+		value: &[u8],
+	) -> dispatch::DispatchResult {
+		Self::ensure_valid(key, value)?;
+		Self::ensure_can_write(writer, owner, key)?;
+
+		Self::write(owner, key, value, None);
+
+		Ok(())
+	}
+
+	/// Get the value stored under `owner` and `key`, `None` if there is none.
+	///
+	/// Unlike the `get` getter, this tells an empty value from a missing one.
+	pub fn value(owner: T::AccountId, key: Vec<u8>) -> Option<Vec<u8>> {
+		if Storage::<T>::contains_key(&owner, &key) {
+			Some(Storage::<T>::get(owner, key))
+		} else {
+			None
+		}
+	}
+
+	/// Get the value stored under `owner` and `key` at the end of the block `at`.
+	///
+	/// Returns `None` if there was no value, `at` is older than the kept history or the key was
+	/// removed or expired since.
+	pub fn value_at(owner: T::AccountId, key: Vec<u8>, at: T::BlockNumber) -> Option<Vec<u8>> {
+		Self::history(owner, key).into_iter()
+			.rev()
+			.find(|version| version.since <= at)
+			.filter(|version| version.is_valid_at(&at))
+			.map(|version| version.value)
+	}
+
+	/// Store `value` as the new version of `key`, valid until `until` if given.
+	///
+	/// The caller has to add `key` to the `Expiries` at `until`.
+	fn write(owner: &T::AccountId, key: &[u8], value: &[u8], until: Option<T::BlockNumber>) {
+		let now = <frame_system::Module<T>>::block_number();
+		Storage::<T>::insert(owner, key, value);
+		History::<T>::mutate(owner, key, |history| {
+			Self::cancel_expiry(owner, key, history, now);
+			Self::close_current(history, now);
+			history.push(Version { value: value.to_vec(), since: now, until });
+			let depth = T::HistoryDepth::get().max(1) as usize;
+			if history.len() > depth {
+				history.drain(..history.len() - depth);
+			}
+		});
+	}
+
+	/// Emit the event for storing `value`.
+	fn deposit_stored(owner: T::AccountId, key: Vec<u8>, value: Vec<u8>, expiry: Option<T::BlockNumber>) {
+		Self::deposit_event(Self::stored_event(owner, key, value, expiry));
+	}
+
+	/// The event for storing `value`, according to `EventMode`.
+	pub(crate) fn stored_event(
+		owner: T::AccountId,
+		key: Vec<u8>,
+		value: Vec<u8>,
+		expiry: Option<T::BlockNumber>,
+	) -> RawEvent<T::AccountId, T::BlockNumber> {
+		match (T::EventMode::get(), expiry) {
+			(EventMode::Full, None) => RawEvent::Stored(owner, key, value),
+			(EventMode::Full, Some(expiry)) => RawEvent::StoredWithTtl(owner, key, value, expiry),
+			(EventMode::Hashed, expiry) => {
+				let hash = Blake2_256::hash(&value);
+				let len = value.len() as u32;
+				match expiry {
+					None => RawEvent::StoredHash(owner, key, hash, len),
+					Some(expiry) => RawEvent::StoredHashWithTtl(owner, key, hash, len, expiry),
+				}
+			}
+		}
+	}
+
+	/// Remove the value of `key` and its history.
+	fn erase(owner: &T::AccountId, key: &[u8]) {
+		let now = <frame_system::Module<T>>::block_number();
+		Storage::<T>::remove(owner, key);
+		let history = History::<T>::take(owner, key);
+		Self::cancel_expiry(owner, key, &history, now);
+	}
+
+	/// Take `key` out of the `Expiries` if its latest version has not expired yet.
+	fn cancel_expiry(owner: &T::AccountId, key: &[u8], history: &[Version<T::BlockNumber>], now: T::BlockNumber) {
+		let expiry = match history.last().and_then(|current| current.until) {
+			Some(expiry) if expiry > now => expiry,
+			_ => return,
+		};
+		let mut expiring = Expiries::<T>::get(expiry, owner);
+		let before = expiring.len();
+		expiring.retain(|expiring_key| expiring_key.as_slice() != key);
+		if expiring.len() < before {
+			let count = ExpiryCount::<T>::get(expiry).saturating_sub(1);
+			if count == 0 {
+				ExpiryCount::<T>::remove(expiry);
+			} else {
+				ExpiryCount::<T>::insert(expiry, count);
+			}
+		}
+		if expiring.is_empty() {
+			Expiries::<T>::remove(expiry, owner);
+		} else {
+			Expiries::<T>::insert(expiry, owner, expiring);
+		}
+	}
+
+	/// End the validity of the latest version at `now` unless it already ended.
+	fn close_current(history: &mut Vec<Version<T::BlockNumber>>, now: T::BlockNumber) {
+		if let Some(current) = history.last_mut() {
+			if current.until.map_or(true, |until| until > now) {
+				current.until = Some(now);
+			}
+		}
+	}
+
+	/// Check that `writer` may write `key` in the namespace of `owner`.
+	pub fn ensure_can_write(writer: &T::AccountId, owner: &T::AccountId, key: &[u8]) -> dispatch::DispatchResult {
+		if writer == owner {
+			return Ok(());
+		}
+		let granted = Delegations::<T>::get(owner, writer).iter().any(|prefix| key.starts_with(prefix));
+		ensure!(granted, Error::<T>::NotDelegated);
+		Ok(())
+	}
+
+	/// Check the length limits and the format registered for `key`, if any.
+	fn ensure_valid(key: &[u8], value: &[u8]) -> dispatch::DispatchResult {
 		ensure!(key.len() <= T::StringLimit::get(), Error::<T>::KeyTooLong);
 		ensure!(value.len() <= T::StringLimit::get(), Error::<T>::ValueTooLong);
+		if let Some(format) = Self::schema(key) {
+			ensure!(format.validate::<T::AccountId>(value), Error::<T>::InvalidFormat);
+		}
+		Ok(())
+	}
+
+	/// Get at most `limit` keys of `acc`, following `start_key` if given.
+	///
+	/// Keys are in storage order, i.e. ordered by their hash.
+	/// The last key of a page is the `start_key` of the next one.
+	pub fn keys(acc: T::AccountId, start_key: Option<Vec<u8>>, limit: u32) -> Vec<Vec<u8>> {
+		// The entries of the account share the storage key without the trailing hashed key.
+		let first_key = start_key.clone().unwrap_or_default();
+		let first = Storage::<T>::hashed_key_for(&acc, &first_key);
+		let prefix = first[..first.len() - first_key.using_encoded(Blake2_128Concat::hash).len()].to_vec();
+		let mut cursor = if start_key.is_some() { first } else { prefix.clone() };
+		let mut keys = Vec::new();
+		while keys.len() < limit as usize {
+			match sp_io::storage::next_key(&cursor) {
+				Some(next) if next.starts_with(&prefix) && next.len() > prefix.len() + 16 => {
+					// The key is encoded after its blake2_128 hash.
+					match Vec::<u8>::decode(&mut &next[prefix.len() + 16..]) {
+						Ok(key) => keys.push(key),
+						Err(_) => break,
+					}
+					cursor = next;
+				}
+				_ => break,
+			}
+		}
+		keys
+	}
+}
 
-		Storage::<T>::insert(acc, key, value);
+/// Provides the values stored under `PUBLIC_KEY` that are valid `ValueFormat::PublicKey`s.
+impl<T: Trait> KeyProvider<T::AccountId> for Module<T> {
+	fn public_key(who: &T::AccountId) -> Option<Vec<u8>> {
+		Some(Storage::<T>::get(who, PUBLIC_KEY))
+			.filter(|key| ValueFormat::PublicKey.validate::<T::AccountId>(key))
+	}
 
-		Ok(())
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_public_key(who: &T::AccountId, key: Vec<u8>) {
+		Self::write(who, PUBLIC_KEY, &key, None);
 	}
-}
\ No newline at end of file
+}
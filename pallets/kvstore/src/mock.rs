@@ -1,6 +1,7 @@
-use crate::{Module, Trait, WeightInfo};
+use crate::{EventMode, Module, Trait, WeightInfo};
 use sp_core::H256;
-use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, traits::Get, weights::Weight};
+use std::cell::RefCell;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
@@ -62,16 +63,53 @@ impl system::Trait for Test {
 
 parameter_types! {
 	pub const StringLimit: usize = 50;
+	pub const BatchLimit: u32 = 3;
+	pub const DelegationsLimit: u32 = 2;
+	pub const HistoryDepth: u32 = 3;
+	pub const MaxExpiriesPerAccount: u32 = 2;
+	pub const MaxExpiriesPerBlock: u32 = 4;
+}
+
+thread_local! {
+	static EVENT_MODE: RefCell<EventMode> = RefCell::new(EventMode::Full);
+}
+
+pub struct MockEventMode;
+impl Get<EventMode> for MockEventMode {
+	fn get() -> EventMode {
+		EVENT_MODE.with(|mode| *mode.borrow())
+	}
+}
+
+pub fn set_event_mode(mode: EventMode) {
+	EVENT_MODE.with(|m| *m.borrow_mut() = mode);
 }
 
 pub struct MockWeightInfo;
 impl WeightInfo for MockWeightInfo {
     fn set(_: u32, _: u32) -> Weight { 0 }
+    fn remove(_: u32) -> Weight { 0 }
+    fn set_many(_: u32) -> Weight { 0 }
+    fn grant() -> Weight { 0 }
+    fn revoke() -> Weight { 0 }
+    fn set_for(_: u32, _: u32) -> Weight { 0 }
+    fn remove_for(_: u32) -> Weight { 0 }
+    fn set_with_ttl(_: u32, _: u32) -> Weight { 0 }
+    fn on_initialize(_: u32) -> Weight { 0 }
+    fn register_schema(_: u32) -> Weight { 0 }
+    fn unregister_schema(_: u32) -> Weight { 0 }
 }
 
 impl Trait for Test {
 	type Event = TestEvent;
 	type StringLimit = StringLimit;
+	type BatchLimit = BatchLimit;
+	type DelegationsLimit = DelegationsLimit;
+	type HistoryDepth = HistoryDepth;
+	type MaxExpiriesPerAccount = MaxExpiriesPerAccount;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type EventMode = MockEventMode;
+	type SchemaOrigin = system::EnsureRoot<u64>;
 	type WeightInfo = MockWeightInfo;
 }
 
@@ -80,6 +118,7 @@ pub type System = system::Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	set_event_mode(EventMode::Full);
 	let storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
 	let mut ext = sp_io::TestExternalities::from(storage);
 	ext.execute_with(|| System::set_block_number(1));
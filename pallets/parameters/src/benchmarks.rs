@@ -0,0 +1,43 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::EventRecord;
+use frame_benchmarking::benchmarks;
+use crate::Module as Parameters;
+
+fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
+	let events = frame_system::Module::<T>::events();
+	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
+	// compare to the last event record
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+benchmarks! {
+	_ { }
+
+	set_parameter {
+		let origin = T::AdminOrigin::successful_origin();
+		let parameter = Parameter::StandardDuration(42u32.into());
+	} : { Parameters::<T>::set_parameter(origin, parameter.clone())? }
+	verify {
+		assert_eq!(Parameters::<T>::standard_duration(), 42u32.into());
+		assert_last_event::<T>(RawEvent::ParameterSet(parameter).into())
+	}
+
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{Test, new_test_ext};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn parameters_set_parameter() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_parameter::<Test>());
+		});
+	}
+}
@@ -0,0 +1,211 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Runtime parameters that can be adjusted by governance.
+//!
+//! Other pallets read the parameters through the `Get` adapters defined here,
+//! e.g. `type StringLimit = pallet_parameters::StringLimitGet<Runtime>;`.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{EnsureOrigin, Get},
+	weights::Weight,
+};
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+mod benchmarks;
+
+use pallet_timestamp as timestamp;
+
+/// A parameter together with its new value.
+#[derive(Clone, Encode, Decode, Debug, PartialEq, Eq)]
+pub enum Parameter<Moment> {
+	StandardDuration(Moment),
+	StringLimit(u32),
+	BulkAccountsLimit(u32),
+	HandlersLimit(u32),
+	EscrowsPerFactoryLimit(u32),
+	ManifestGateways(Vec<Vec<u8>>),
+}
+
+pub trait Trait: frame_system::Trait + timestamp::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// The origin allowed to change parameters.
+	type AdminOrigin: EnsureOrigin<Self::Origin>;
+	/// The largest `StringLimit` governance can set.
+	///
+	/// The limits are capped at the values the weights of the pallets using them were benchmarked with.
+	type MaxStringLimit: Get<u32>;
+	/// The largest `BulkAccountsLimit` governance can set.
+	type MaxBulkAccountsLimit: Get<u32>;
+	/// The largest `HandlersLimit` governance can set.
+	type MaxHandlersLimit: Get<u32>;
+	/// The largest `EscrowsPerFactoryLimit` governance can set.
+	type MaxEscrowsPerFactoryLimit: Get<u32>;
+	/// The most `ManifestGateways` governance can set.
+	type MaxManifestGateways: Get<u32>;
+	/// The longest URL prefix allowed in `ManifestGateways`.
+	type MaxManifestGatewayLength: Get<u32>;
+	type WeightInfo: WeightInfo;
+}
+
+pub trait WeightInfo {
+	fn set_parameter() -> Weight;
+}
+
+impl WeightInfo for () {
+	fn set_parameter() -> Weight {
+		0
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Parameters {
+		// The defaults are the runtime constants the parameters replaced,
+		// so chains upgraded from them keep their limits until governance changes them.
+
+		/// The duration for which an escrow stays open.
+		StandardDuration get(fn standard_duration) config(): T::Moment = 8_640_000u32.into();
+		/// The maximum length for strings/byte arrays passed into functions.
+		StringLimit get(fn string_limit) config(): u32 = 1000;
+		/// The maximum number of accounts that can be transferred to via bulk transfer.
+		pub BulkAccountsLimit get(fn bulk_accounts_limit) config(): u32 = 100;
+		/// The maximum amount of trusted handlers per escrow.
+		HandlersLimit get(fn handlers_limit) config(): u32 = 20;
+		/// The maximum number of escrows per factory.
+		EscrowsPerFactoryLimit get(fn escrows_per_factory_limit) config(): u32 = 20;
+		/// The URL prefixes manifests are fetched from to check their hash.
+		///
+		/// Manifest checks are disabled while empty.
+		ManifestGateways get(fn manifest_gateways) config(): Vec<Vec<u8>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where Moment = <T as timestamp::Trait>::Moment {
+		/// A parameter was changed. [parameter]
+		ParameterSet(Parameter<Moment>),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Parameters cannot be set to zero.
+		ZeroValue,
+		/// The limit is above its maximum.
+		ValueTooLarge,
+		/// A manifest gateway is empty, too long or does not end with `/`.
+		InvalidGateway,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Set the given `parameter` to its new value.
+		///
+		/// Lowered limits only apply to new operations, existing state is kept.
+		/// Limits have to be between one and their configured maximum.
+		/// Manifest gateways are URL prefixes ending with `/`, bounded in number and length.
+		/// Requires `AdminOrigin`.
+		#[weight = <T as Trait>::WeightInfo::set_parameter()]
+		pub fn set_parameter(origin, parameter: Parameter<T::Moment>) {
+			T::AdminOrigin::ensure_origin(origin)?;
+			match parameter.clone() {
+				Parameter::StandardDuration(duration) => {
+					ensure!(!duration.is_zero(), Error::<T>::ZeroValue);
+					<StandardDuration<T>>::put(duration);
+				}
+				Parameter::StringLimit(limit) => {
+					ensure!(limit > 0, Error::<T>::ZeroValue);
+					ensure!(limit <= T::MaxStringLimit::get(), Error::<T>::ValueTooLarge);
+					StringLimit::put(limit);
+				}
+				Parameter::BulkAccountsLimit(limit) => {
+					ensure!(limit > 0, Error::<T>::ZeroValue);
+					ensure!(limit <= T::MaxBulkAccountsLimit::get(), Error::<T>::ValueTooLarge);
+					BulkAccountsLimit::put(limit);
+				}
+				Parameter::HandlersLimit(limit) => {
+					ensure!(limit > 0, Error::<T>::ZeroValue);
+					ensure!(limit <= T::MaxHandlersLimit::get(), Error::<T>::ValueTooLarge);
+					HandlersLimit::put(limit);
+				}
+				Parameter::EscrowsPerFactoryLimit(limit) => {
+					ensure!(limit > 0, Error::<T>::ZeroValue);
+					ensure!(limit <= T::MaxEscrowsPerFactoryLimit::get(), Error::<T>::ValueTooLarge);
+					EscrowsPerFactoryLimit::put(limit);
+				}
+				Parameter::ManifestGateways(gateways) => {
+					ensure!(gateways.len() as u32 <= T::MaxManifestGateways::get(), Error::<T>::ValueTooLarge);
+					for gateway in &gateways {
+						ensure!(
+							gateway.len() as u32 <= T::MaxManifestGatewayLength::get() && gateway.ends_with(b"/"),
+							Error::<T>::InvalidGateway
+						);
+					}
+					ManifestGateways::put(gateways);
+				}
+			}
+			Self::deposit_event(RawEvent::ParameterSet(parameter));
+		}
+	}
+}
+
+/// Reads `StandardDuration` from storage.
+pub struct StandardDurationGet<T>(PhantomData<T>);
+impl<T: Trait> Get<T::Moment> for StandardDurationGet<T> {
+	fn get() -> T::Moment {
+		Module::<T>::standard_duration()
+	}
+}
+
+/// Reads `StringLimit` from storage.
+pub struct StringLimitGet<T>(PhantomData<T>);
+impl<T: Trait> Get<usize> for StringLimitGet<T> {
+	fn get() -> usize {
+		Module::<T>::string_limit() as usize
+	}
+}
+
+/// Reads `BulkAccountsLimit` from storage.
+pub struct BulkAccountsLimitGet<T>(PhantomData<T>);
+impl<T: Trait> Get<usize> for BulkAccountsLimitGet<T> {
+	fn get() -> usize {
+		Module::<T>::bulk_accounts_limit() as usize
+	}
+}
+
+/// Reads `HandlersLimit` from storage.
+pub struct HandlersLimitGet<T>(PhantomData<T>);
+impl<T: Trait> Get<u32> for HandlersLimitGet<T> {
+	fn get() -> u32 {
+		Module::<T>::handlers_limit()
+	}
+}
+
+/// Reads `EscrowsPerFactoryLimit` from storage.
+pub struct EscrowsPerFactoryLimitGet<T>(PhantomData<T>);
+impl<T: Trait> Get<u32> for EscrowsPerFactoryLimitGet<T> {
+	fn get() -> u32 {
+		Module::<T>::escrows_per_factory_limit()
+	}
+}
+
+/// Reads `ManifestGateways` from storage.
+pub struct ManifestGatewaysGet<T>(PhantomData<T>);
+impl<T: Trait> Get<Vec<Vec<u8>>> for ManifestGatewaysGet<T> {
+	fn get() -> Vec<Vec<u8>> {
+		Module::<T>::manifest_gateways()
+	}
+}
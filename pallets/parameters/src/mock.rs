@@ -0,0 +1,114 @@
+use crate::{Module, Trait};
+use sp_core::H256;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+};
+use frame_system as system;
+
+impl_outer_origin! {
+	pub enum Origin for Test {}
+}
+
+// Configure a mock runtime to test the pallet.
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+
+mod ParametersPallet {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		ParametersPallet<T>,
+		system<T>,
+	}
+}
+
+impl system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MaxStringLimit: u32 = 1000;
+	pub const MaxBulkAccountsLimit: u32 = 100;
+	pub const MaxHandlersLimit: u32 = 50;
+	pub const MaxEscrowsPerFactoryLimit: u32 = 50;
+	pub const MaxManifestGateways: u32 = 2;
+	pub const MaxManifestGatewayLength: u32 = 32;
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type AdminOrigin = system::EnsureRoot<u64>;
+	type MaxStringLimit = MaxStringLimit;
+	type MaxBulkAccountsLimit = MaxBulkAccountsLimit;
+	type MaxHandlersLimit = MaxHandlersLimit;
+	type MaxEscrowsPerFactoryLimit = MaxEscrowsPerFactoryLimit;
+	type MaxManifestGateways = MaxManifestGateways;
+	type MaxManifestGatewayLength = MaxManifestGatewayLength;
+	type WeightInfo = ();
+}
+
+pub type Parameters = Module<Test>;
+pub type System = system::Module<Test>;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	crate::GenesisConfig::<Test> {
+		standard_duration: 1000,
+		string_limit: 100,
+		bulk_accounts_limit: 10,
+		handlers_limit: 10,
+		escrows_per_factory_limit: 20,
+		manifest_gateways: vec![],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	let mut ext = sp_io::TestExternalities::from(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
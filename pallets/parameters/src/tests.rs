@@ -0,0 +1,117 @@
+use crate::{
+	mock::*, BulkAccountsLimitGet, Error, EscrowsPerFactoryLimitGet, HandlersLimitGet, ManifestGatewaysGet, Parameter,
+	RawEvent, StandardDurationGet, StringLimitGet,
+};
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchError, traits::Get};
+
+fn last_event() -> TestEvent {
+	frame_system::Module::<Test>::events().pop().expect("Event expected").event
+}
+
+#[test]
+fn genesis_values_are_exposed() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(StandardDurationGet::<Test>::get(), 1000);
+		assert_eq!(StringLimitGet::<Test>::get(), 100);
+		assert_eq!(BulkAccountsLimitGet::<Test>::get(), 10);
+		assert_eq!(HandlersLimitGet::<Test>::get(), 10);
+		assert_eq!(EscrowsPerFactoryLimitGet::<Test>::get(), 20);
+		assert_eq!(ManifestGatewaysGet::<Test>::get(), Vec::<Vec<u8>>::new());
+	});
+}
+
+#[test]
+fn upgraded_chains_keep_the_replaced_constants() {
+	// Chains upgraded from the runtime constants have no parameters in storage.
+	let storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(StandardDurationGet::<Test>::get(), 8_640_000);
+		assert_eq!(StringLimitGet::<Test>::get(), 1000);
+		assert_eq!(BulkAccountsLimitGet::<Test>::get(), 100);
+		assert_eq!(HandlersLimitGet::<Test>::get(), 20);
+		assert_eq!(EscrowsPerFactoryLimitGet::<Test>::get(), 20);
+		assert_eq!(ManifestGatewaysGet::<Test>::get(), Vec::<Vec<u8>>::new());
+
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::HandlersLimit(30)));
+		assert_eq!(HandlersLimitGet::<Test>::get(), 30);
+	});
+}
+
+#[test]
+fn set_parameter_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::StandardDuration(500)));
+		assert_eq!(last_event(), TestEvent::ParametersPallet(RawEvent::ParameterSet(Parameter::StandardDuration(500))));
+		assert_eq!(StandardDurationGet::<Test>::get(), 500);
+
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::StringLimit(42)));
+		assert_eq!(StringLimitGet::<Test>::get(), 42);
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::BulkAccountsLimit(5)));
+		assert_eq!(BulkAccountsLimitGet::<Test>::get(), 5);
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::HandlersLimit(30)));
+		assert_eq!(HandlersLimitGet::<Test>::get(), 30);
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::EscrowsPerFactoryLimit(1)));
+		assert_eq!(EscrowsPerFactoryLimitGet::<Test>::get(), 1);
+		let gateways = vec![b"https://ipfs.io/ipfs/".to_vec()];
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::ManifestGateways(gateways.clone())));
+		assert_eq!(ManifestGatewaysGet::<Test>::get(), gateways);
+	});
+}
+
+#[test]
+fn set_parameter_negative() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Parameters::set_parameter(Origin::signed(1), Parameter::StringLimit(42)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Parameters::set_parameter(Origin::root(), Parameter::StandardDuration(0)),
+			Error::<Test>::ZeroValue
+		);
+		assert_noop!(
+			Parameters::set_parameter(Origin::root(), Parameter::HandlersLimit(0)),
+			Error::<Test>::ZeroValue
+		);
+		assert_noop!(
+			Parameters::set_parameter(Origin::root(), Parameter::StringLimit(MaxStringLimit::get() + 1)),
+			Error::<Test>::ValueTooLarge
+		);
+		assert_noop!(
+			Parameters::set_parameter(Origin::root(), Parameter::BulkAccountsLimit(MaxBulkAccountsLimit::get() + 1)),
+			Error::<Test>::ValueTooLarge
+		);
+		assert_noop!(
+			Parameters::set_parameter(Origin::root(), Parameter::HandlersLimit(MaxHandlersLimit::get() + 1)),
+			Error::<Test>::ValueTooLarge
+		);
+		assert_noop!(
+			Parameters::set_parameter(
+				Origin::root(),
+				Parameter::EscrowsPerFactoryLimit(MaxEscrowsPerFactoryLimit::get() + 1)
+			),
+			Error::<Test>::ValueTooLarge
+		);
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::StringLimit(MaxStringLimit::get())));
+	});
+}
+
+#[test]
+fn manifest_gateways_are_validated() {
+	new_test_ext().execute_with(|| {
+		let gateway = b"https://ipfs.io/ipfs/".to_vec();
+		assert_noop!(
+			Parameters::set_parameter(Origin::root(), Parameter::ManifestGateways(vec![gateway.clone(); 3])),
+			Error::<Test>::ValueTooLarge
+		);
+		for invalid in vec![b"".to_vec(), b"https://ipfs.io/ipfs".to_vec(), vec![b'/'; 33]] {
+			assert_noop!(
+				Parameters::set_parameter(Origin::root(), Parameter::ManifestGateways(vec![gateway.clone(), invalid])),
+				Error::<Test>::InvalidGateway
+			);
+		}
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::ManifestGateways(vec![gateway; 2])));
+		assert_ok!(Parameters::set_parameter(Origin::root(), Parameter::ManifestGateways(vec![])));
+		assert_eq!(ManifestGatewaysGet::<Test>::get(), Vec::<Vec<u8>>::new());
+	});
+}
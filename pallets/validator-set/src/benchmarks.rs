@@ -0,0 +1,74 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use sp_std::prelude::*;
+
+use frame_system::{EventRecord, RawOrigin};
+use frame_benchmarking::{account, benchmarks};
+use crate::Module as ValidatorSet;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Trait>(generic_event: <T as Trait>::Event) {
+	let events = frame_system::Module::<T>::events();
+	let system_event: <T as frame_system::Trait>::Event = generic_event.into();
+	// compare to the last event record
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+fn set_validators<T: Trait>(count: u32) -> Vec<T::AccountId> {
+	let mut validators: Vec<T::AccountId> = (0..count).map(|i| account("validator", i, SEED)).collect();
+	validators.sort();
+	<Validators<T>>::put(validators.clone());
+	validators
+}
+
+benchmarks! {
+	_ { }
+
+	add_validator {
+		let v in 1 .. (T::MaxValidators::get() - 1);
+		set_validators::<T>(v);
+		let origin = T::AddRemoveOrigin::successful_origin();
+		let who: T::AccountId = account("new_validator", 0, SEED);
+		<pallet_session::Module<T>>::set_keys(RawOrigin::Signed(who.clone()).into(), Default::default(), vec![])?;
+	} : { ValidatorSet::<T>::add_validator(origin, who.clone())? }
+	verify {
+		assert!(ValidatorSet::<T>::validators().contains(&who));
+		assert_last_event::<T>(RawEvent::ValidatorAdded(who).into())
+	}
+
+	remove_validator {
+		let v in (T::MinValidators::get() + 1) .. T::MaxValidators::get();
+		let validators = set_validators::<T>(v);
+		let origin = T::AddRemoveOrigin::successful_origin();
+		let who = validators[0].clone();
+	} : { ValidatorSet::<T>::remove_validator(origin, who.clone())? }
+	verify {
+		assert!(!ValidatorSet::<T>::validators().contains(&who));
+		assert_last_event::<T>(RawEvent::ValidatorRemoved(who).into())
+	}
+
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{Test, new_test_ext};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn validator_set_add_validator() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_add_validator::<Test>());
+		});
+	}
+
+	#[test]
+	fn validator_set_remove_validator() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_remove_validator::<Test>());
+		});
+	}
+}
@@ -0,0 +1,235 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Validator set management for a proof-of-authority network.
+//!
+//! Governance adds and removes validators. The changes are applied by `pallet_session`
+//! at the next session rotation, for which this pallet acts as `SessionManager`.
+//! Validators register their keys via `session.setKeys`.
+//!
+//! The pallet also handles offences reported by `pallet_offences`, e.g. GRANDPA equivocations.
+//! An offender is disabled for the rest of the session and removed from the validators unless
+//! that would go below `MinValidators`.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, ensure,
+	traits::{Contains, EnsureOrigin, Get, ValidatorRegistration},
+	weights::Weight,
+};
+use sp_runtime::{traits::Convert, Perbill};
+use sp_staking::{offence::{OffenceDetails, OnOffenceHandler}, SessionIndex};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+mod benchmarks;
+
+pub trait Trait: frame_system::Trait + pallet_session::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// The origin allowed to add and remove validators.
+	type AddRemoveOrigin: EnsureOrigin<Self::Origin>;
+	/// The number of validators that cannot be removed.
+	type MinValidators: Get<u32>;
+	/// The maximum number of validators.
+	type MaxValidators: Get<u32>;
+	type WeightInfo: WeightInfo;
+}
+
+pub trait WeightInfo {
+	fn add_validator(v: u32) -> Weight;
+	fn remove_validator(v: u32) -> Weight;
+}
+
+impl WeightInfo for () {
+	fn add_validator(_v: u32) -> Weight {
+		0
+	}
+	fn remove_validator(_v: u32) -> Weight {
+		0
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ValidatorSet {
+		/// The validators of the upcoming sessions, sorted.
+		Validators get(fn validators) build(|config: &GenesisConfig<T>| {
+			let mut validators = config.validators.clone();
+			validators.sort();
+			validators
+		}): Vec<T::AccountId>;
+
+		/// Whether the validators changed since the last planned session.
+		///
+		/// Set at genesis, so that the session pallet plans the sorted genesis validators.
+		ValidatorsChanged get(fn validators_changed) build(|_| true): bool;
+	}
+	add_extra_genesis {
+		config(validators): Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+		/// A validator was added and will be active from the session after next. [validator]
+		ValidatorAdded(AccountId),
+		/// A validator was removed and will be inactive from the session after next. [validator]
+		ValidatorRemoved(AccountId),
+		/// A validator committed an offence and was disabled for the rest of the session. [validator]
+		ValidatorDisabled(AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account is already a validator.
+		AlreadyValidator,
+		/// The account is not a validator.
+		NotValidator,
+		/// Removing the validator would go below `MinValidators`.
+		TooFewValidators,
+		/// Adding the validator would exceed `MaxValidators`.
+		TooManyValidators,
+		/// The account has not set session keys.
+		NoSessionKeys,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Add `who` to the validators.
+		///
+		/// `who` has to have set session keys, otherwise they could not author blocks.
+		/// Requires `AddRemoveOrigin`.
+		#[weight = <T as Trait>::WeightInfo::add_validator(T::MaxValidators::get())]
+		pub fn add_validator(origin, who: T::AccountId) {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+			let mut validators = Self::validators();
+			ensure!((validators.len() as u32) < T::MaxValidators::get(), Error::<T>::TooManyValidators);
+			let index = validators.binary_search(&who).err().ok_or(Error::<T>::AlreadyValidator)?;
+			let registered = T::ValidatorIdOf::convert(who.clone())
+				.map_or(false, |id| <pallet_session::Module<T>>::is_registered(&id));
+			ensure!(registered, Error::<T>::NoSessionKeys);
+			validators.insert(index, who.clone());
+			<Validators<T>>::put(validators);
+			ValidatorsChanged::put(true);
+			Self::deposit_event(RawEvent::ValidatorAdded(who));
+		}
+
+		/// Remove `who` from the validators.
+		///
+		/// Requires `AddRemoveOrigin`.
+		#[weight = <T as Trait>::WeightInfo::remove_validator(T::MaxValidators::get())]
+		pub fn remove_validator(origin, who: T::AccountId) {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+			let mut validators = Self::validators();
+			ensure!(validators.len() as u32 > T::MinValidators::get(), Error::<T>::TooFewValidators);
+			let index = validators.binary_search(&who).map_err(|_| Error::<T>::NotValidator)?;
+			validators.remove(index);
+			<Validators<T>>::put(validators);
+			ValidatorsChanged::put(true);
+			Self::deposit_event(RawEvent::ValidatorRemoved(who));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Remove `who` from the validators if they are one and the minimum allows it.
+	fn try_remove_validator(who: &T::AccountId) -> bool {
+		let mut validators = Self::validators();
+		if validators.len() as u32 <= T::MinValidators::get() {
+			return false;
+		}
+		match validators.binary_search(who) {
+			Ok(index) => {
+				validators.remove(index);
+				<Validators<T>>::put(validators);
+				ValidatorsChanged::put(true);
+				true
+			}
+			Err(_) => false,
+		}
+	}
+}
+
+impl<T: Trait> pallet_session::SessionManager<T::AccountId> for Module<T> {
+	fn new_session(_new_index: u32) -> Option<Vec<T::AccountId>> {
+		if ValidatorsChanged::take() {
+			Some(Self::validators())
+		} else {
+			None
+		}
+	}
+
+	fn end_session(_end_index: u32) {}
+
+	fn start_session(_start_index: u32) {}
+}
+
+impl<T: Trait> pallet_session::historical::SessionManager<T::AccountId, T::AccountId> for Module<T> {
+	fn new_session(new_index: u32) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v.clone(), v)).collect())
+	}
+
+	fn end_session(_end_index: u32) {}
+
+	fn start_session(_start_index: u32) {}
+}
+
+/// The current validators, e.g. to restrict who may report off-chain work.
+impl<T: Trait> Contains<T::AccountId> for Module<T> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		Self::validators()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add(who: &T::AccountId) {
+		let mut validators = Self::validators();
+		if let Err(index) = validators.binary_search(who) {
+			validators.insert(index, who.clone());
+			<Validators<T>>::put(validators);
+		}
+	}
+}
+
+/// Slashing is not supported, so the slash fraction is ignored.
+impl<T: Trait, I> OnOffenceHandler<T::AccountId, (T::AccountId, I), Weight> for Module<T> where
+	T: pallet_session::Trait<ValidatorId = <T as frame_system::Trait>::AccountId>,
+{
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, (T::AccountId, I)>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+	) -> Result<Weight, ()> {
+		for details in offenders {
+			let (who, _) = &details.offender;
+			// Errors if the validator is not part of the current session, e.g. already rotated out.
+			if <pallet_session::Module<T>>::disable(who).is_ok() {
+				Self::deposit_event(RawEvent::ValidatorDisabled(who.clone()));
+			}
+			if Self::try_remove_validator(who) {
+				Self::deposit_event(RawEvent::ValidatorRemoved(who.clone()));
+			}
+		}
+		Ok(<T as frame_system::Trait>::DbWeight::get().reads_writes(4, 4).saturating_mul(offenders.len() as Weight))
+	}
+
+	fn can_report() -> bool {
+		true
+	}
+}
+
+/// Validators are identified by their account id.
+pub struct ValidatorOf<T>(PhantomData<T>);
+impl<T: Trait> Convert<T::AccountId, Option<T::AccountId>> for ValidatorOf<T> {
+	fn convert(account: T::AccountId) -> Option<T::AccountId> {
+		Some(account)
+	}
+}
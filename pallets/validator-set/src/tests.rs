@@ -0,0 +1,209 @@
+use crate::{mock::*, Error, RawEvent};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchError, DispatchResultWithPostInfo},
+	traits::{Contains, KeyOwnerProofSystem, UnfilteredDispatchable},
+};
+use pallet_grandpa::AuthorityId as GrandpaId;
+use sp_core::H256;
+use sp_finality_grandpa::{self as fg_primitives, EquivocationProof, RoundNumber, SetId, KEY_TYPE};
+
+fn last_event() -> TestEvent {
+	frame_system::Module::<Test>::events().pop().expect("Event expected").event
+}
+
+/// Whether `event` was deposited, offence handlers run before the offence event is.
+fn deposited(event: RawEvent<u64>) -> bool {
+	frame_system::Module::<Test>::events().into_iter()
+		.any(|record| record.event == TestEvent::ValidatorSetPallet(event.clone()))
+}
+
+/// Two prevotes of `validator` for the `targets` at the same height and round.
+fn equivocation_proof(
+	validator: u64,
+	set_id: SetId,
+	round: RoundNumber,
+	targets: (H256, H256),
+) -> EquivocationProof<H256, u64> {
+	forged_equivocation_proof(validator, validator, set_id, round, targets)
+}
+
+/// Like `equivocation_proof`, but the prevotes of `validator` are signed by `signer`.
+fn forged_equivocation_proof(
+	validator: u64,
+	signer: u64,
+	set_id: SetId,
+	round: RoundNumber,
+	targets: (H256, H256),
+) -> EquivocationProof<H256, u64> {
+	let signer = keyring(signer);
+	let signed_prevote = |target_hash| {
+		let prevote = finality_grandpa::Prevote { target_hash, target_number: 1 };
+		let message = finality_grandpa::Message::Prevote(prevote.clone());
+		let payload = fg_primitives::localized_payload(round, set_id, &message);
+		(prevote, signer.sign(&payload).into())
+	};
+	EquivocationProof::new(
+		set_id,
+		fg_primitives::Equivocation::Prevote(finality_grandpa::Equivocation {
+			round_number: round,
+			identity: keyring(validator).public().into(),
+			first: signed_prevote(targets.0),
+			second: signed_prevote(targets.1),
+		}),
+	)
+}
+
+/// Dispatch an unsigned equivocation report, as included by block authors.
+fn report_unsigned(
+	proof: EquivocationProof<H256, u64>,
+	key_owner_proof: <Test as pallet_grandpa::Trait>::KeyOwnerProof,
+) -> DispatchResultWithPostInfo {
+	pallet_grandpa::Call::<Test>::report_equivocation_unsigned(proof, key_owner_proof)
+		.dispatch_bypass_filter(Origin::none())
+}
+
+/// Report an equivocation of `validator` in the current session the way block authors do.
+fn report_equivocation(validator: u64) {
+	let key: GrandpaId = keyring(validator).public().into();
+	let key_owner_proof = Historical::prove((KEY_TYPE, key)).expect("validator has keys in the session");
+	let targets = (H256::repeat_byte(1), H256::repeat_byte(2));
+	let proof = equivocation_proof(validator, Grandpa::current_set_id(), 1, targets);
+	assert_ok!(report_unsigned(proof, key_owner_proof));
+}
+
+#[test]
+fn genesis_validators_are_sorted() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3]);
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+	});
+}
+
+#[test]
+fn add_validator_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Session::set_keys(Origin::signed(4), session_keys(4), vec![]));
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+		assert_eq!(last_event(), TestEvent::ValidatorSetPallet(RawEvent::ValidatorAdded(4)));
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3, 4]);
+		assert!(ValidatorSet::validators_changed());
+
+		// The new validator is queued in the next session and active in the one after.
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3]);
+		assert!(!ValidatorSet::validators_changed());
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2, 3, 4]);
+	});
+}
+
+#[test]
+fn remove_validator_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 2));
+		assert_eq!(last_event(), TestEvent::ValidatorSetPallet(RawEvent::ValidatorRemoved(2)));
+		assert_eq!(ValidatorSet::validators(), vec![1, 3]);
+		Session::rotate_session();
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 3]);
+	});
+}
+
+#[test]
+fn validators_are_members() {
+	new_test_ext().execute_with(|| {
+		assert!(ValidatorSet::contains(&2));
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 2));
+		assert!(!ValidatorSet::contains(&2));
+		assert_eq!(ValidatorSet::sorted_members(), vec![1, 3]);
+	});
+}
+
+#[test]
+fn add_remove_validator_negative() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 4), DispatchError::BadOrigin);
+		assert_noop!(ValidatorSet::remove_validator(Origin::signed(1), 3), DispatchError::BadOrigin);
+		assert_noop!(ValidatorSet::add_validator(Origin::root(), 3), Error::<Test>::AlreadyValidator);
+		assert_noop!(ValidatorSet::remove_validator(Origin::root(), 4), Error::<Test>::NotValidator);
+		assert_noop!(ValidatorSet::add_validator(Origin::root(), 4), Error::<Test>::NoSessionKeys);
+
+		for validator in 4..=6 {
+			assert_ok!(Session::set_keys(Origin::signed(validator), session_keys(validator), vec![]));
+		}
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 5));
+		assert_noop!(ValidatorSet::add_validator(Origin::root(), 6), Error::<Test>::TooManyValidators);
+
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 5));
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 4));
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 3));
+		assert_noop!(ValidatorSet::remove_validator(Origin::root(), 2), Error::<Test>::TooFewValidators);
+	});
+}
+
+#[test]
+fn key_ownership_proofs() {
+	new_test_ext().execute_with(|| {
+		for validator in ValidatorSet::validators() {
+			let key: GrandpaId = keyring(validator).public().into();
+			let proof = Historical::prove((KEY_TYPE, key.clone())).unwrap();
+			assert_eq!(Historical::check_proof((KEY_TYPE, key), proof), Some((validator, validator)));
+		}
+		let unknown: GrandpaId = keyring(4).public().into();
+		assert!(Historical::prove((KEY_TYPE, unknown)).is_none());
+	});
+}
+
+#[test]
+fn equivocation_disables_and_removes_validator() {
+	new_test_ext().execute_with(|| {
+		report_equivocation(2);
+		assert_eq!(Session::disabled_validators(), vec![1]);
+		assert!(deposited(RawEvent::ValidatorDisabled(2)));
+		assert!(deposited(RawEvent::ValidatorRemoved(2)));
+		assert_eq!(ValidatorSet::validators(), vec![1, 3]);
+
+		// The offender stays disabled until it leaves the active validators.
+		Session::rotate_session();
+		assert_eq!(Session::disabled_validators(), vec![1]);
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 3]);
+		assert!(Session::disabled_validators().is_empty());
+	});
+}
+
+#[test]
+fn equivocation_keeps_min_validators() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 3));
+		Session::rotate_session();
+		Session::rotate_session();
+		assert_eq!(Session::validators(), vec![1, 2]);
+
+		report_equivocation(1);
+		assert_eq!(Session::disabled_validators(), vec![0]);
+		assert!(deposited(RawEvent::ValidatorDisabled(1)));
+		assert!(!deposited(RawEvent::ValidatorRemoved(1)));
+		assert_eq!(ValidatorSet::validators(), vec![1, 2]);
+	});
+}
+
+#[test]
+fn invalid_equivocation_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let key: GrandpaId = keyring(2).public().into();
+		let key_owner_proof = Historical::prove((KEY_TYPE, key)).unwrap();
+		// Votes for the same block are no equivocation.
+		let same = (H256::repeat_byte(1), H256::repeat_byte(1));
+		let proof = equivocation_proof(2, Grandpa::current_set_id(), 1, same);
+		assert!(report_unsigned(proof, key_owner_proof.clone()).is_err());
+		// The votes have to be signed by the reported validator.
+		let targets = (H256::repeat_byte(1), H256::repeat_byte(2));
+		let proof = forged_equivocation_proof(2, 3, Grandpa::current_set_id(), 1, targets);
+		assert!(report_unsigned(proof, key_owner_proof).is_err());
+		assert!(Session::disabled_validators().is_empty());
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3]);
+	});
+}
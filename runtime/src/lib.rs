@@ -7,18 +7,25 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use sp_std::prelude::*;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{
+	crypto::KeyTypeId, OpaqueMetadata,
+	u32_trait::{_1, _2, _3},
+};
 use sp_runtime::{
-	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature, ModuleId, Percent,
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, IdentityLookup, Verify, IdentifyAccount, NumberFor, Saturating,
+	BlakeTwo256, Block as BlockT, IdentityLookup, Verify, IdentifyAccount, NumberFor, OpaqueKeys, Saturating,
+	SaturatedConversion,
 };
 use sp_api::impl_runtime_apis;
+use frame_support::traits::{Contains, ContainsLengthBound};
+use frame_system::{EnsureOneOf, EnsureRoot};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_grandpa::fg_primitives;
+use pallet_session::historical as pallet_session_historical;
 use sp_version::RuntimeVersion;
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
@@ -41,6 +48,8 @@ pub use frame_support::{
 /// Import the pallets.
 pub use pallet_escrow;
 pub use pallet_kvstore;
+pub use pallet_parameters;
+pub use pallet_validator_set;
 
 mod weights;
 
@@ -101,10 +110,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -198,6 +207,111 @@ impl frame_system::Trait for Runtime {
 	type SystemWeightInfo = ();
 }
 
+parameter_types! {
+	pub const MinValidators: u32 = 1;
+	pub const MaxValidators: u32 = 100;
+}
+
+impl pallet_validator_set::Trait for Runtime {
+	type Event = Event;
+	type AddRemoveOrigin = GovernanceOrigin;
+	type MinValidators = MinValidators;
+	type MaxValidators = MaxValidators;
+	type WeightInfo = weights::pallet_validator_set::WeightInfo;
+}
+
+parameter_types! {
+	pub const Period: BlockNumber = HOURS;
+	pub const Offset: BlockNumber = 0;
+	pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl pallet_session::Trait for Runtime {
+	type Event = Event;
+	type ValidatorId = <Self as frame_system::Trait>::AccountId;
+	type ValidatorIdOf = pallet_validator_set::ValidatorOf<Self>;
+	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+	type WeightInfo = ();
+}
+
+impl pallet_session::historical::Trait for Runtime {
+	type FullIdentification = AccountId;
+	type FullIdentificationOf = pallet_validator_set::ValidatorOf<Self>;
+}
+
+parameter_types! {
+	pub OffencesWeightSoftLimit: Weight = Perbill::from_percent(60) * MaximumBlockWeight::get();
+}
+
+impl pallet_offences::Trait for Runtime {
+	type Event = Event;
+	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = ValidatorSet;
+	type WeightSoftLimit = OffencesWeightSoftLimit;
+}
+
+parameter_types! {
+	pub const UncleGenerations: BlockNumber = 0;
+}
+
+// Provides the block author, who is credited with GRANDPA equivocation reports.
+impl pallet_authorship::Trait for Runtime {
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime where
+	Call: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = Call;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::CreateSignedTransaction<C> for Runtime where
+	Call: From<C>,
+{
+	fn create_transaction<A: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(Call, <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+		use codec::Encode;
+		// Mortal for roughly half the block hash history, starting at the parent block.
+		let period = BlockHashCount::get()
+			.checked_next_power_of_two()
+			.map(|c| c / 2)
+			.unwrap_or(2) as u64;
+		let current_block = System::block_number()
+			.saturated_into::<u64>()
+			.saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+		);
+		let payload = SignedPayload::new(call, extra).ok()?;
+		let signature = payload.using_encoded(|payload| A::sign(payload, public))?;
+		let (call, extra, _) = payload.deconstruct();
+		Some((call, (account, signature, extra)))
+	}
+}
+
 impl pallet_aura::Trait for Runtime {
 	type AuthorityId = AuraId;
 }
@@ -206,7 +320,7 @@ impl pallet_grandpa::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
 
-	type KeyOwnerProofSystem = ();
+	type KeyOwnerProofSystem = Historical;
 
 	type KeyOwnerProof =
 		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -216,7 +330,7 @@ impl pallet_grandpa::Trait for Runtime {
 		GrandpaId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation = ();
+	type HandleEquivocation = pallet_grandpa::EquivocationHandler<Self::KeyOwnerIdentification, Offences>;
 
 	type WeightInfo = ();
 }
@@ -262,41 +376,300 @@ impl pallet_transaction_payment::Trait for Runtime {
 	type FeeMultiplierUpdate = ();
 }
 
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+
+impl pallet_collective::Trait<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = ();
+}
+
+/// Root or a two-thirds majority of the council.
+///
+/// Used for changes to parameters and funds. Once sudo is removed, root is only
+/// reachable via democracy.
+pub type GovernanceOrigin = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>,
+>;
+
+/// Root or more than half of the council.
+pub type MoreThanHalfCouncil = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>,
+>;
+
+impl pallet_membership::Trait<pallet_membership::Instance1> for Runtime {
+	type Event = Event;
+	type AddOrigin = MoreThanHalfCouncil;
+	type RemoveOrigin = MoreThanHalfCouncil;
+	type SwapOrigin = MoreThanHalfCouncil;
+	type ResetOrigin = MoreThanHalfCouncil;
+	type PrimeOrigin = MoreThanHalfCouncil;
+	type MembershipInitialized = Council;
+	type MembershipChanged = Council;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Trait for Runtime {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+	pub const VotingPeriod: BlockNumber = 7 * DAYS;
+	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+	pub const InstantAllowed: bool = true;
+	pub const MinimumDeposit: Balance = 1_000_000_000_000_000;
+	pub const EnactmentPeriod: BlockNumber = 2 * DAYS;
+	pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+	pub const PreimageByteDeposit: Balance = 1_000_000_000_000;
+	pub const MaxVotes: u32 = 100;
+}
+
+impl pallet_democracy::Trait for Runtime {
+	type Proposal = Call;
+	type Event = Event;
+	type Currency = Balances;
+	type EnactmentPeriod = EnactmentPeriod;
+	type LaunchPeriod = LaunchPeriod;
+	type VotingPeriod = VotingPeriod;
+	type MinimumDeposit = MinimumDeposit;
+	/// A majority of the council can have the next scheduled referendum be a straight
+	/// majority-carries vote.
+	type ExternalOrigin = pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	/// A super-majority of the council can have the next scheduled referendum be a
+	/// negative-turnout-bias vote.
+	type ExternalMajorityOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	/// A unanimous council can have the next scheduled referendum be a straight default-carries
+	/// (NTB) vote.
+	type ExternalDefaultOrigin = pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	/// Two thirds of the council can have an external proposal voted on more quickly.
+	type FastTrackOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	/// A unanimous council can have an external proposal voted on immediately.
+	type InstantOrigin = pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	type InstantAllowed = InstantAllowed;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	/// Two thirds of the council can cancel a passed referendum.
+	type CancellationOrigin = pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	/// Any council member can veto a coming council proposal, however they can
+	/// only do it once and it lasts only for the cooloff period.
+	type VetoOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+	type CooloffPeriod = CooloffPeriod;
+	type PreimageByteDeposit = PreimageByteDeposit;
+	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+	type Slash = Treasury;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type MaxVotes = MaxVotes;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = 1_000_000_000_000_000;
+	pub const SpendPeriod: BlockNumber = DAYS;
+	pub const Burn: Permill = Permill::from_percent(0);
+	pub const TipCountdown: BlockNumber = DAYS;
+	pub const TipFindersFee: Percent = Percent::from_percent(20);
+	pub const TipReportDepositBase: Balance = 1_000_000_000_000_000;
+	pub const DataDepositPerByte: Balance = 1_000_000_000_000;
+	pub const BountyDepositBase: Balance = 1_000_000_000_000_000;
+	pub const BountyDepositPayoutDelay: BlockNumber = DAYS;
+	pub const BountyUpdatePeriod: BlockNumber = 14 * DAYS;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: Balance = 5_000_000_000_000_000;
+	pub const MaximumReasonLength: u32 = 16384;
+	pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+}
+
+/// There is no body of tippers yet, so tipping is disabled.
+pub struct NoTippers;
+
+impl Contains<AccountId> for NoTippers {
+	fn sorted_members() -> Vec<AccountId> {
+		Vec::new()
+	}
+}
+
+impl ContainsLengthBound for NoTippers {
+	fn min_len() -> usize {
+		0
+	}
+	fn max_len() -> usize {
+		0
+	}
+}
+
+impl pallet_treasury::Trait for Runtime {
+	type ModuleId = TreasuryModuleId;
+	type Currency = Balances;
+	type ApproveOrigin = GovernanceOrigin;
+	type RejectOrigin = MoreThanHalfCouncil;
+	type Tippers = NoTippers;
+	type TipCountdown = TipCountdown;
+	type TipFindersFee = TipFindersFee;
+	type TipReportDepositBase = TipReportDepositBase;
+	type DataDepositPerByte = DataDepositPerByte;
+	type Event = Event;
+	type OnSlash = ();
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
+	type MaximumReasonLength = MaximumReasonLength;
+	type BurnDestination = ();
+	type WeightInfo = ();
+}
+
 impl pallet_sudo::Trait for Runtime {
 	type Event = Event;
 	type Call = Call;
 }
 
 parameter_types! {
-	pub const StringLimit: usize = 1000;
+	// The values the escrow and kvstore weights were benchmarked with.
+	pub const MaxStringLimit: u32 = 1000;
+	pub const MaxBulkAccountsLimit: u32 = 100;
+	pub const MaxHandlersLimit: u32 = 20;
+	pub const MaxEscrowsPerFactoryLimit: u32 = 20;
+}
+
+parameter_types! {
+	pub const MaxManifestGateways: u32 = 10;
+	pub const MaxManifestGatewayLength: u32 = 256;
+}
+
+impl pallet_parameters::Trait for Runtime {
+	type Event = Event;
+	type AdminOrigin = GovernanceOrigin;
+	type MaxStringLimit = MaxStringLimit;
+	type MaxBulkAccountsLimit = MaxBulkAccountsLimit;
+	type MaxHandlersLimit = MaxHandlersLimit;
+	type MaxEscrowsPerFactoryLimit = MaxEscrowsPerFactoryLimit;
+	type MaxManifestGateways = MaxManifestGateways;
+	type MaxManifestGatewayLength = MaxManifestGatewayLength;
+	type WeightInfo = weights::pallet_parameters::WeightInfo;
+}
+
+type StringLimit = pallet_parameters::StringLimitGet<Runtime>;
+
+parameter_types! {
+	pub const KVStoreBatchLimit: u32 = 100;
+	pub const KVStoreDelegationsLimit: u32 = 10;
+	pub const KVStoreHistoryDepth: u32 = 10;
+	pub const KVStoreMaxExpiriesPerAccount: u32 = 50;
+	pub const KVStoreMaxExpiriesPerBlock: u32 = 200;
+	// Events only carry value hashes to keep blocks small.
+	pub const KVStoreEventMode: pallet_kvstore::EventMode = pallet_kvstore::EventMode::Hashed;
 }
 
 impl pallet_kvstore::Trait for Runtime {
 	type Event = Event;
 	type StringLimit = StringLimit;
+	type BatchLimit = KVStoreBatchLimit;
+	type DelegationsLimit = KVStoreDelegationsLimit;
+	type HistoryDepth = KVStoreHistoryDepth;
+	type MaxExpiriesPerAccount = KVStoreMaxExpiriesPerAccount;
+	type MaxExpiriesPerBlock = KVStoreMaxExpiriesPerBlock;
+	type EventMode = KVStoreEventMode;
+	type SchemaOrigin = GovernanceOrigin;
 	type WeightInfo = weights::pallet_kvstore::WeightInfo;
 }
 
 parameter_types! {
-	pub const BulkAccountsLimit: usize = 100;
 	pub const BulkBalanceLimit: Balance = 1_000_000_000 * 1_000_000_000_000_000;
 }
 
 parameter_types! {
-	pub const StandardDuration: Moment = 8_640_000;
-	pub const HandlersLimit: u32 = 20;
-	pub const EscrowsPerFactoryLimit: u32 = 20;
+	pub const OraclesLimit: u32 = 5;
+	pub const RequireOracleKeys: bool = true;
+	pub const IntermediateResultsLimit: u32 = 10;
+	pub const RevealWindow: Moment = 432_000;
+	pub const ResultsAcceptanceWindow: Moment = 864_000;
+	pub const ManifestChecksPerBlock: u32 = 10;
+}
+
+/// Signs results attestations with sr25519 keys generated in the benchmarking keystore.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct EscrowBenchmarkSigner;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl EscrowBenchmarkSigner {
+	const KEY_TYPE: KeyTypeId = KeyTypeId(*b"bnch");
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_escrow::BenchmarkSigner<AccountId, Signature> for EscrowBenchmarkSigner {
+	fn account() -> AccountId {
+		use sp_runtime::app_crypto::RuntimePublic;
+		let public = sp_core::sr25519::Public::generate_pair(Self::KEY_TYPE, None);
+		sp_runtime::MultiSigner::from(public).into_account()
+	}
+
+	fn sign(account: &AccountId, message: &[u8]) -> Signature {
+		use sp_runtime::app_crypto::RuntimePublic;
+		let public = sp_core::sr25519::Public::from_raw(account.clone().into());
+		public.sign(Self::KEY_TYPE, &message)
+			.expect("the key was generated by `account`; qed")
+			.into()
+	}
 }
 
 impl pallet_escrow::Trait for Runtime {
 	type Event = Event;
-	type StandardDuration = StandardDuration;
+	type StandardDuration = pallet_parameters::StandardDurationGet<Runtime>;
 	type StringLimit = StringLimit;
 	type Currency = Balances;
-	type BulkAccountsLimit = BulkAccountsLimit;
+	type BulkAccountsLimit = pallet_parameters::BulkAccountsLimitGet<Runtime>;
 	type BulkBalanceLimit = BulkBalanceLimit;
-	type HandlersLimit = HandlersLimit;
-	type EscrowsPerFactoryLimit = EscrowsPerFactoryLimit;
+	type HandlersLimit = pallet_parameters::HandlersLimitGet<Runtime>;
+	type EscrowsPerFactoryLimit = pallet_parameters::EscrowsPerFactoryLimitGet<Runtime>;
+	type OraclesLimit = OraclesLimit;
+	type IntermediateResultsLimit = IntermediateResultsLimit;
+	type OracleSignature = Signature;
+	type OracleSigner = <Signature as Verify>::Signer;
+	type RevealWindow = RevealWindow;
+	type ResultsAcceptanceWindow = ResultsAcceptanceWindow;
+	type ProtocolFeeOrigin = GovernanceOrigin;
+	type ProtocolFeeDestination = Treasury;
+	type KeyProvider = KVStore;
+	type RequireOracleKeys = RequireOracleKeys;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkSigner = EscrowBenchmarkSigner;
+	type ManifestGateways = pallet_parameters::ManifestGatewaysGet<Runtime>;
+	type ManifestChecksPerBlock = ManifestChecksPerBlock;
+	type AuthorityId = pallet_escrow::crypto::ManifestCheckerId;
+	type ManifestCheckers = ValidatorSet;
 	type WeightInfo = weights::pallet_escrow::WeightInfo;
 }
 
@@ -310,13 +683,24 @@ construct_runtime!(
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
 		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+		ValidatorSet: pallet_validator_set::{Module, Call, Storage, Event<T>, Config<T>},
+		Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
+		Historical: pallet_session_historical::{Module},
+		Offences: pallet_offences::{Module, Call, Storage, Event},
 		Aura: pallet_aura::{Module, Config<T>, Inherent},
-		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
+		Authorship: pallet_authorship::{Module, Storage},
+		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event, ValidateUnsigned},
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		Treasury: pallet_treasury::{Module, Call, Storage, Config, Event<T>},
+		Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		CouncilMembership: pallet_membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
+		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
+		Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
+		Parameters: pallet_parameters::{Module, Call, Storage, Config<T>, Event<T>},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		KVStore: pallet_kvstore::{Module, Call, Storage, Event<T>},
-		Escrow: pallet_escrow::{Module, Call, Storage, Event<T>},
+		Escrow: pallet_escrow::{Module, Call, Storage, Event<T>, Config},
 	}
 );
 
@@ -343,6 +727,8 @@ pub type SignedExtra = (
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+/// The payload being signed in transactions.
+pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -441,23 +827,29 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: fg_primitives::EquivocationProof<
+			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: fg_primitives::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			use codec::Encode;
+
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
 		}
 	}
 
@@ -476,6 +868,64 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kvstore_runtime_api::KVStoreApi<Block, AccountId, BlockNumber> for Runtime {
+		fn keys(account: AccountId, start_key: Option<Vec<u8>>, limit: u32) -> Vec<Vec<u8>> {
+			KVStore::keys(account, start_key, limit)
+		}
+
+		fn get(account: AccountId, key: Vec<u8>) -> Option<Vec<u8>> {
+			KVStore::value(account, key)
+		}
+
+		fn get_many(account: AccountId, keys: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>> {
+			keys.into_iter().map(|key| KVStore::value(account.clone(), key)).collect()
+		}
+
+		fn history(account: AccountId, key: Vec<u8>) -> Vec<pallet_kvstore::Version<BlockNumber>> {
+			KVStore::history(account, key)
+		}
+
+		fn value_at(account: AccountId, key: Vec<u8>, at: BlockNumber) -> Option<Vec<u8>> {
+			KVStore::value_at(account, key, at)
+		}
+
+		fn schema(key: Vec<u8>) -> Option<pallet_kvstore::ValueFormat> {
+			KVStore::schema(key)
+		}
+	}
+
+	impl pallet_escrow_runtime_api::EscrowApi<Block, AccountId, BlockNumber, Balance> for Runtime {
+		fn intermediate_results(
+			id: pallet_escrow::EscrowId,
+		) -> Vec<pallet_escrow::IntermediateResult<BlockNumber, AccountId>> {
+			Escrow::intermediate_results(id)
+		}
+
+		fn oracles(id: pallet_escrow::EscrowId) -> Vec<pallet_escrow::Oracle<AccountId, Balance>> {
+			Escrow::oracles(id)
+		}
+
+		fn oracle_keys(id: pallet_escrow::EscrowId) -> Vec<(AccountId, Vec<u8>)> {
+			Escrow::oracle_keys(id)
+		}
+
+		fn preview_payout(
+			id: pallet_escrow::EscrowId,
+			amounts: Vec<Balance>,
+		) -> Option<pallet_escrow::PayoutPreview<AccountId, Balance>> {
+			Escrow::preview_payout(id, amounts)
+		}
+
+		fn escrows_of(
+			who: AccountId,
+			party: pallet_escrow::EscrowParty,
+			start_after: Option<pallet_escrow::EscrowId>,
+			limit: u32,
+		) -> Vec<pallet_escrow::EscrowId> {
+			Escrow::escrows_of(who, party, start_after, limit)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -507,6 +957,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_kvstore, KVStore);
 			add_benchmark!(params, batches, pallet_escrow, Escrow);
+			add_benchmark!(params, batches, pallet_parameters, Parameters);
+			add_benchmark!(params, batches, pallet_validator_set, ValidatorSet);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
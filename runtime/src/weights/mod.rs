@@ -1,2 +1,4 @@
 pub mod pallet_kvstore;
-pub mod pallet_escrow;
\ No newline at end of file
+pub mod pallet_escrow;
+pub mod pallet_parameters;
+pub mod pallet_validator_set;
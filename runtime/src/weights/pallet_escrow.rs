@@ -8,55 +8,105 @@ use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
 pub struct WeightInfo;
 impl pallet_escrow::WeightInfo for WeightInfo {
 	fn create_factory() -> Weight {
-		(30_036_000 as Weight)
+		(19_729_000 as Weight)
 			.saturating_add(DbWeight::get().reads(1 as Weight))
 			.saturating_add(DbWeight::get().writes(2 as Weight))
 	}
 	fn create() -> Weight {
-		(87_556_000 as Weight)
-			.saturating_add(DbWeight::get().reads(3 as Weight))
-			.saturating_add(DbWeight::get().writes(7 as Weight))
+		(111_165_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11 as Weight))
+			.saturating_add(DbWeight::get().writes(16 as Weight))
+	}
+	fn create_from_terms() -> Weight {
+		(109_165_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11 as Weight))
+			.saturating_add(DbWeight::get().writes(16 as Weight))
 	}
 	fn add_trusted_handlers(h: u32, ) -> Weight {
-		(25_763_000 as Weight)
-			.saturating_add((4_656_000 as Weight).saturating_mul(h as Weight))
-			.saturating_add(DbWeight::get().reads(2 as Weight))
+		(20_070_000 as Weight)
+			.saturating_add((1_599_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
 			.saturating_add(DbWeight::get().writes(1 as Weight))
 			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(h as Weight)))
 	}
 	fn abort(h: u32, f: u32, ) -> Weight {
-		(145_556_000 as Weight)
-			.saturating_add((893_000 as Weight).saturating_mul(h as Weight))
-			.saturating_add((339_000 as Weight).saturating_mul(f as Weight))
+		(141_101_000 as Weight)
+			.saturating_add((3_102_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add((3_122_000 as Weight).saturating_mul(f as Weight))
 			.saturating_add(DbWeight::get().reads(4 as Weight))
-			.saturating_add(DbWeight::get().writes(8 as Weight))
+			.saturating_add(DbWeight::get().writes(23 as Weight))
 			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(h as Weight)))
 	}
 	fn cancel() -> Weight {
-		(122_983_000 as Weight)
+		(59_929_000 as Weight)
 			.saturating_add(DbWeight::get().reads(3 as Weight))
 			.saturating_add(DbWeight::get().writes(2 as Weight))
 	}
 	fn complete() -> Weight {
-		(36_158_000 as Weight)
-			.saturating_add(DbWeight::get().reads(3 as Weight))
-			.saturating_add(DbWeight::get().writes(1 as Weight))
+		(169_422_000 as Weight)
+			.saturating_add(DbWeight::get().reads(10 as Weight))
+			.saturating_add(DbWeight::get().writes(7 as Weight))
+	}
+	fn fund() -> Weight {
+		(194_508_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(7 as Weight))
 	}
 	fn note_intermediate_results() -> Weight {
-		(50_986_000 as Weight)
-			.saturating_add(DbWeight::get().reads(3 as Weight))
+		(73_529_000 as Weight)
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
 	}
 	fn store_final_results() -> Weight {
-		(35_708_000 as Weight)
+		(60_011_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn store_signed_final_results() -> Weight {
+		(256_730_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn commit_results() -> Weight {
+		(66_691_000 as Weight)
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn reveal_results() -> Weight {
+		(99_087_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn flag_unrevealed() -> Weight {
+		(48_163_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn accept_results() -> Weight {
+		(42_620_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn reject_results() -> Weight {
+		(45_848_000 as Weight)
 			.saturating_add(DbWeight::get().reads(3 as Weight))
 			.saturating_add(DbWeight::get().writes(1 as Weight))
 	}
 	fn bulk_payout(b: u32, ) -> Weight {
-		(335_054_000 as Weight)
-			.saturating_add((74_610_000 as Weight).saturating_mul(b as Weight))
-			.saturating_add(DbWeight::get().reads(6 as Weight))
+		(243_221_000 as Weight)
+			.saturating_add((27_080_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(DbWeight::get().reads(14 as Weight))
 			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(b as Weight)))
-			.saturating_add(DbWeight::get().writes(4 as Weight))
+			.saturating_add(DbWeight::get().writes(8 as Weight))
 			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(b as Weight)))
 	}
+	fn set_protocol_fee() -> Weight {
+		(17_053_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn report_manifest_check() -> Weight {
+		(40_377_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
 }
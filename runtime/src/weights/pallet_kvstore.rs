@@ -7,10 +7,74 @@ use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
 
 pub struct WeightInfo;
 impl pallet_kvstore::WeightInfo for WeightInfo {
-	fn set(k: u32, v: u32, ) -> Weight {
-		(31_869_000 as Weight)
-			.saturating_add((2_000 as Weight).saturating_mul(k as Weight))
-			.saturating_add((2_000 as Weight).saturating_mul(v as Weight))
+	// WARNING! Some components were not used: ["v"]
+	fn set(k: u32, _v: u32, ) -> Weight {
+		(22_989_000 as Weight)
+			.saturating_add((19_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn remove(k: u32, ) -> Weight {
+		(35_192_000 as Weight)
+			.saturating_add((31_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn set_many(n: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((37_351_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	fn grant() -> Weight {
+		(37_815_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn revoke() -> Weight {
+		(30_613_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_for(k: u32, v: u32, ) -> Weight {
+		(44_322_000 as Weight)
+			.saturating_add((13_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add((1_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn remove_for(k: u32, ) -> Weight {
+		(38_907_000 as Weight)
+			.saturating_add((28_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	// WARNING! Some components were not used: ["v"]
+	fn set_with_ttl(k: u32, _v: u32, ) -> Weight {
+		(109_551_000 as Weight)
+			.saturating_add((28_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn on_initialize(n: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((26_319_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+			.saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn register_schema(k: u32, ) -> Weight {
+		(22_934_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn unregister_schema(k: u32, ) -> Weight {
+		(23_941_000 as Weight)
+			.saturating_add((12_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
 			.saturating_add(DbWeight::get().writes(1 as Weight))
 	}
 }
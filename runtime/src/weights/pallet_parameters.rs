@@ -0,0 +1,14 @@
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.1
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+pub struct WeightInfo;
+impl pallet_parameters::WeightInfo for WeightInfo {
+	fn set_parameter() -> Weight {
+		(19_519_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+}
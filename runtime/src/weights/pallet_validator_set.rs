@@ -0,0 +1,22 @@
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.1
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+pub struct WeightInfo;
+impl pallet_validator_set::WeightInfo for WeightInfo {
+	fn add_validator(v: u32, ) -> Weight {
+		(28_914_000 as Weight)
+			.saturating_add((404_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn remove_validator(v: u32, ) -> Weight {
+		(24_931_000 as Weight)
+			.saturating_add((160_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+}